@@ -0,0 +1,202 @@
+//! Sending and receiving binary data as base64 text.
+
+use crate::codec::{base64_value, BASE64_ALPHABET};
+use crate::ext::Base64DecodeError;
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// Wraps a `MutBlockingTx` and base64 encodes everything sent through it,
+/// using the standard alphabet.
+///
+/// Every three octets sent become four characters on the wire. Up to two
+/// octets are held back until the group is complete; `flush_base64` sends
+/// them as a final, `=` padded, group. `flush` only flushes the wrapped
+/// port, so call `flush_base64` first at the end of the data.
+///
+/// If the wrapped port fails part way through a group, the characters
+/// already sent are remembered, and retrying the same octet sends the rest
+/// of the group.
+///
+/// ```
+/// use embedded_serial::MutBlockingTx;
+/// use embedded_serial::adapters::Base64Tx;
+/// use embedded_serial::testing::VecTx;
+///
+/// let mut tx = Base64Tx::new(VecTx::<16>::new());
+/// tx.puts(b"hello").unwrap();
+/// tx.flush_base64().unwrap();
+/// assert_eq!(tx.into_inner().as_slice(), b"aGVsbG8=");
+/// ```
+pub struct Base64Tx<T> {
+    inner: T,
+    pending: [u8; 2],
+    len: usize,
+    sent: usize,
+}
+
+impl<T> Base64Tx<T>
+    where T: MutBlockingTx
+{
+    /// Wrap a transmitter.
+    pub fn new(inner: T) -> Base64Tx<T> {
+        Base64Tx {
+            inner,
+            pending: [0; 2],
+            len: 0,
+            sent: 0,
+        }
+    }
+
+    /// Send any octets held back as a final group, padded with `=`. Does
+    /// nothing if no octets are held back.
+    pub fn flush_base64(&mut self) -> Result<(), T::Error> {
+        if self.len == 0 {
+            return Ok(());
+        }
+        let group = [self.pending[0], self.pending[1], 0];
+        self.send_group(&group, self.len)?;
+        self.len = 0;
+        Ok(())
+    }
+
+    /// Release the wrapped transmitter. Any octets held back are lost, so
+    /// call `flush_base64` first.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Send the first `len` octets of `group` as four characters, skipping
+    /// any sent before an earlier attempt failed.
+    fn send_group(&mut self, group: &[u8; 3], len: usize) -> Result<(), T::Error> {
+        let bits = u32::from(group[0]) << 16 | u32::from(group[1]) << 8 | u32::from(group[2]);
+        for idx in self.sent..4 {
+            let ch = if idx <= len {
+                BASE64_ALPHABET[((bits >> (18 - 6 * idx)) & 0x3F) as usize]
+            } else {
+                b'='
+            };
+            self.inner.putc(ch)?;
+            self.sent = idx + 1;
+        }
+        self.sent = 0;
+        Ok(())
+    }
+}
+
+impl<T> MutBlockingTx for Base64Tx<T>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    /// Add an octet to the current group, sending the group once it has
+    /// three. If sending fails, the octet isn't added, and the next call
+    /// carries on from the first character which wasn't sent.
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        if self.len < 2 {
+            self.pending[self.len] = ch;
+            self.len += 1;
+            return Ok(());
+        }
+        let group = [self.pending[0], self.pending[1], ch];
+        self.send_group(&group, 3)?;
+        self.len = 0;
+        Ok(())
+    }
+
+    /// Flush the wrapped port. Any octets held back stay held back, as
+    /// sending them would end the encoded data; use `flush_base64` for
+    /// that.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+/// Wraps a `MutBlockingRx` and decodes the base64 text received on it,
+/// using the standard alphabet.
+///
+/// Each group of four characters is decoded as it arrives, and handed out
+/// an octet at a time. Whitespace (space, tab, `\r` and `\n`) between
+/// characters is skipped, so line-wrapped base64 can be read.
+pub struct Base64Rx<T> {
+    inner: T,
+    decoded: [u8; 3],
+    next: usize,
+    len: usize,
+}
+
+impl<T> Base64Rx<T>
+    where T: MutBlockingRx
+{
+    /// Wrap a receiver.
+    pub fn new(inner: T) -> Base64Rx<T> {
+        Base64Rx {
+            inner,
+            decoded: [0; 3],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Release the wrapped receiver. Any decoded octets not yet read are
+    /// lost.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Read and decode the next group of four characters.
+    fn read_group(&mut self) -> Result<(), Base64DecodeError<T::Error>> {
+        let mut group = [0u8; 4];
+        for space in group.iter_mut() {
+            *space = loop {
+                match self.inner.getc().map_err(Base64DecodeError::Inner)? {
+                    b' ' | b'\t' | b'\r' | b'\n' => {}
+                    ch => break ch,
+                }
+            };
+        }
+        let padding = match (group[2], group[3]) {
+            (b'=', b'=') => 2,
+            (b'=', _) => return Err(Base64DecodeError::InvalidPadding),
+            (_, b'=') => 1,
+            _ => 0,
+        };
+        let mut bits: u32 = 0;
+        for ch in &group[..4 - padding] {
+            let value = base64_value(*ch).ok_or(Base64DecodeError::InvalidCharacter(*ch))?;
+            bits = (bits << 6) | u32::from(value);
+        }
+        bits <<= 6 * padding;
+        self.decoded = [(bits >> 16) as u8, (bits >> 8) as u8, bits as u8];
+        self.next = 0;
+        self.len = 3 - padding;
+        Ok(())
+    }
+}
+
+impl<T> MutBlockingRx for Base64Rx<T>
+    where T: MutBlockingRx
+{
+    /// `Base64DecodeError::BufferTooSmall` is never returned. After any
+    /// other decode error, the group is lost.
+    type Error = Base64DecodeError<T::Error>;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        if self.next == self.len {
+            self.read_group()?;
+        }
+        let ch = self.decoded[self.next];
+        self.next += 1;
+        Ok(ch)
+    }
+
+    /// The number of decoded octets waiting. Characters still waiting in the
+    /// port aren't counted.
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        Ok(self.len - self.next)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
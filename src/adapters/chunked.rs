@@ -0,0 +1,61 @@
+//! Splitting large writes into smaller ones.
+
+use crate::MutBlockingTx;
+
+/// Wraps a `MutBlockingTx` and passes `puts` on to it in pieces of at most
+/// `CHUNK` octets, for ports (such as some USB CDC-ACM stacks) which
+/// misbehave when given more than their internal buffer holds at once.
+///
+/// `putc` and `flush` are passed straight through.
+pub struct ChunkedTx<T, const CHUNK: usize> {
+    inner: T,
+}
+
+impl<T, const CHUNK: usize> ChunkedTx<T, CHUNK>
+    where T: MutBlockingTx
+{
+    const CHECK: () = assert!(CHUNK >= 1, "a ChunkedTx needs chunks of at least one octet");
+
+    /// Wrap a transmitter.
+    pub fn new(inner: T) -> ChunkedTx<T, CHUNK> {
+        let () = Self::CHECK;
+        ChunkedTx { inner }
+    }
+
+    /// Release the wrapped transmitter.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, const CHUNK: usize> MutBlockingTx for ChunkedTx<T, CHUNK>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.inner.putc(ch)
+    }
+
+    /// Send `data` to the wrapped port one chunk at a time. If a chunk
+    /// fails, you get the total number of octets sent, across all the
+    /// chunks, and the error.
+    fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        for (idx, chunk) in data.as_ref().chunks(CHUNK).enumerate() {
+            self.inner.puts(chunk).map_err(|(sent, e)| (idx * CHUNK + sent, e))?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,105 @@
+//! One deadline for a whole transaction, rather than a timeout per octet.
+
+use crate::{MutBlockingRxWithTimeout, RxReady};
+
+/// The ways in which a `GlobalTimeout` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DeadlineError<E> {
+    /// The deadline passed before the operation finished.
+    Expired,
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Wraps a `MutBlockingRxWithTimeout` and puts a single deadline on
+/// everything read through it.
+///
+/// `deadline_passed` is called before every call to the wrapped port, and
+/// once it returns true every read fails with `DeadlineError::Expired`. In
+/// between, the wrapped port is called with the `step` timeout, which only
+/// sets how often the deadline is checked, so a read which times out is
+/// simply tried again. A 100 octet `gets_wait` therefore takes no longer
+/// than the deadline allows (plus one `step`), however the octets are
+/// spread out.
+///
+/// The timeout type is `()`, as the deadline replaces it.
+pub struct GlobalTimeout<T, F>
+    where T: MutBlockingRxWithTimeout
+{
+    inner: T,
+    step: T::Timeout,
+    deadline_passed: F,
+}
+
+impl<T, F> GlobalTimeout<T, F>
+    where T: MutBlockingRxWithTimeout,
+          F: Fn() -> bool
+{
+    /// Wrap a receiver. `deadline_passed` returns true once the deadline
+    /// has passed.
+    pub fn new(inner: T, step: T::Timeout, deadline_passed: F) -> GlobalTimeout<T, F> {
+        GlobalTimeout {
+            inner,
+            step,
+            deadline_passed,
+        }
+    }
+
+    /// Release the wrapped receiver.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn check(&self) -> Result<(), DeadlineError<T::Error>> {
+        if (self.deadline_passed)() {
+            Err(DeadlineError::Expired)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<T, F> MutBlockingRxWithTimeout for GlobalTimeout<T, F>
+    where T: MutBlockingRxWithTimeout,
+          F: Fn() -> bool
+{
+    type Timeout = ();
+    type Error = DeadlineError<T::Error>;
+
+    /// Read an octet, blocking until one arrives or the deadline passes.
+    /// This never returns `Ok(None)`.
+    fn getc_wait(&mut self, _timeout: &()) -> Result<Option<u8>, Self::Error> {
+        loop {
+            self.check()?;
+            if let Some(ch) = self.inner.getc_wait(&self.step).map_err(DeadlineError::Inner)? {
+                return Ok(Some(ch));
+            }
+        }
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available().map_err(DeadlineError::Inner)
+    }
+}
+
+impl<T, F> RxReady for GlobalTimeout<T, F>
+    where T: RxReady,
+          F: Fn() -> bool
+{
+    /// Wait for data to arrive, or fail with `DeadlineError::Expired` once
+    /// the deadline passes. This never returns `Ok(false)`.
+    fn wait_for_rx_ready(&mut self, _timeout: &()) -> Result<bool, Self::Error> {
+        loop {
+            self.check()?;
+            if self.inner.wait_for_rx_ready(&self.step).map_err(DeadlineError::Inner)? {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
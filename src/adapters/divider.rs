@@ -0,0 +1,53 @@
+//! Sending every octet several times over.
+
+use crate::MutBlockingTx;
+
+/// Wraps a `MutBlockingTx` and sends every octet `N` times in a row.
+///
+/// Some bootloaders and bit-banged receivers take a majority vote over
+/// repeated copies of each octet; old AVR programmers at 1200 baud, for
+/// example, want each octet three times. `N` must be at least one, which is
+/// checked at compile time.
+pub struct FrequencyDivider<T, const N: usize> {
+    inner: T,
+}
+
+impl<T, const N: usize> FrequencyDivider<T, N>
+    where T: MutBlockingTx
+{
+    const CHECK: () = assert!(N >= 1, "FrequencyDivider needs N >= 1");
+
+    /// Wrap a transmitter.
+    pub fn new(inner: T) -> FrequencyDivider<T, N> {
+        let () = Self::CHECK;
+        FrequencyDivider { inner }
+    }
+
+    /// Release the wrapped transmitter.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, const N: usize> MutBlockingTx for FrequencyDivider<T, N>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        for _ in 0..N {
+            self.inner.putc(ch)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,88 @@
+//! Echoing received data back to the sender.
+
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// Wraps a port and sends every octet it receives straight back, before
+/// handing it to the caller - as a terminal's remote echo does, or for a
+/// loopback diagnostic.
+///
+/// Echoing starts enabled, and can be turned off and on with `set_echo`.
+/// Anything sent through the `MutBlockingTx` impl goes to the port
+/// unchanged.
+pub struct EchoSerial<T> {
+    inner: T,
+    echo_enabled: bool,
+}
+
+impl<T> EchoSerial<T>
+    where T: MutBlockingTx + MutBlockingRx<Error = <T as MutBlockingTx>::Error>
+{
+    /// Wrap a port, with echoing enabled.
+    pub fn new(inner: T) -> EchoSerial<T> {
+        EchoSerial {
+            inner,
+            echo_enabled: true,
+        }
+    }
+
+    /// Turn echoing on or off.
+    pub fn set_echo(&mut self, enabled: bool) {
+        self.echo_enabled = enabled;
+    }
+
+    /// Returns true if received octets are being echoed.
+    pub fn is_echo_enabled(&self) -> bool {
+        self.echo_enabled
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingTx for EchoSerial<T>
+    where T: MutBlockingTx + MutBlockingRx<Error = <T as MutBlockingTx>::Error>
+{
+    type Error = <T as MutBlockingTx>::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.inner.putc(ch)
+    }
+
+    fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.inner.puts(data)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+impl<T> MutBlockingRx for EchoSerial<T>
+    where T: MutBlockingTx + MutBlockingRx<Error = <T as MutBlockingTx>::Error>
+{
+    type Error = <T as MutBlockingTx>::Error;
+
+    /// Read an octet and, if echoing is enabled, send it back. If the echo
+    /// fails, that error is returned and the octet is lost.
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        let ch = self.inner.getc()?;
+        if self.echo_enabled {
+            self.inner.putc(ch)?;
+        }
+        Ok(ch)
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,140 @@
+//! Falling back to a redundant port.
+
+use crate::error::FatalError;
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// Which of a `FalloverSerial`'s ports is in use.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ActivePort {
+    /// The primary port.
+    Primary,
+    /// The secondary port, after the primary failed.
+    Secondary,
+}
+
+/// The error returned by `FalloverSerial`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FalloverError<EP, ES> {
+    /// The primary port returned an error which wasn't fatal, so it is
+    /// still in use.
+    Primary(EP),
+    /// The secondary port returned an error.
+    Secondary(ES),
+}
+
+/// Wraps a primary and a secondary port, and uses the primary until it
+/// returns an error which `FatalError::is_fatal` says is fatal. It then
+/// switches to the secondary, and stays there until `reset_primary` is
+/// called.
+///
+/// The operation which failed on the primary is tried again on the
+/// secondary, so no data is lost in the switch. Each switch is counted, and
+/// `take_switchover` reports whether one has happened since it was last
+/// called, so it can be logged.
+pub struct FalloverSerial<P, S> {
+    primary: P,
+    secondary: S,
+    active: ActivePort,
+    switchovers: u32,
+    switched: bool,
+}
+
+impl<P, S> FalloverSerial<P, S> {
+    /// Wrap two ports, starting with the primary.
+    pub fn new(primary: P, secondary: S) -> FalloverSerial<P, S> {
+        FalloverSerial {
+            primary,
+            secondary,
+            active: ActivePort::Primary,
+            switchovers: 0,
+            switched: false,
+        }
+    }
+
+    /// The port currently in use.
+    pub fn active_port(&self) -> ActivePort {
+        self.active
+    }
+
+    /// Go back to using the primary port, e.g. once it has been repaired. If
+    /// it is still broken, the next fatal error switches to the secondary
+    /// again.
+    pub fn reset_primary(&mut self) {
+        self.active = ActivePort::Primary;
+    }
+
+    /// Returns true if there has been a switch to the secondary port since
+    /// this was last called.
+    pub fn take_switchover(&mut self) -> bool {
+        core::mem::replace(&mut self.switched, false)
+    }
+
+    /// The number of times there has been a switch to the secondary port.
+    pub fn switchover_count(&self) -> u32 {
+        self.switchovers
+    }
+
+    /// Release the wrapped ports.
+    pub fn into_inner(self) -> (P, S) {
+        (self.primary, self.secondary)
+    }
+
+    /// Run `on_primary` if the primary is active, switching to the secondary
+    /// and running `on_secondary` if it fails fatally.
+    fn run<R, EP, ES>(&mut self,
+                      on_primary: impl FnOnce(&mut P) -> Result<R, EP>,
+                      on_secondary: impl FnOnce(&mut S) -> Result<R, ES>)
+                      -> Result<R, FalloverError<EP, ES>>
+        where EP: FatalError
+    {
+        if self.active == ActivePort::Primary {
+            match on_primary(&mut self.primary) {
+                Err(e) if e.is_fatal() => {
+                    self.active = ActivePort::Secondary;
+                    self.switchovers = self.switchovers.wrapping_add(1);
+                    self.switched = true;
+                }
+                result => return result.map_err(FalloverError::Primary),
+            }
+        }
+        on_secondary(&mut self.secondary).map_err(FalloverError::Secondary)
+    }
+}
+
+impl<P, S> MutBlockingTx for FalloverSerial<P, S>
+    where P: MutBlockingTx,
+          S: MutBlockingTx,
+          P::Error: FatalError
+{
+    type Error = FalloverError<P::Error, S::Error>;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.run(|p| p.putc(ch), |s| s.putc(ch))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.run(|p| p.flush(), |s| s.flush())
+    }
+}
+
+impl<P, S> MutBlockingRx for FalloverSerial<P, S>
+    where P: MutBlockingRx,
+          S: MutBlockingRx,
+          P::Error: FatalError
+{
+    type Error = FalloverError<P::Error, S::Error>;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        self.run(|p| p.getc(), |s| s.getc())
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.run(|p| p.bytes_available(), |s| s.bytes_available())
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,148 @@
+//! XON/XOFF software flow control.
+
+use crate::{MutBlockingRx, MutBlockingTx, MutNonBlockingRx};
+
+/// The octet (DC1) which asks the other end to resume sending.
+const XON: u8 = 0x11;
+
+/// The octet (DC3) which asks the other end to stop sending.
+const XOFF: u8 = 0x13;
+
+/// The ways in which a `SoftFlowControl` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FlowControlError<E> {
+    /// An octet arrived while the receive buffer was full, so it was lost -
+    /// the other end hasn't honoured our XOFF.
+    Overflow,
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Wraps a port and adds XON/XOFF (0x11/0x13) software flow control.
+///
+/// Whenever the adapter is used it reads everything waiting on the port into
+/// an `N` octet buffer, watching for XON and XOFF from the other end. After
+/// an XOFF, `putc` waits (still reading) until an XON arrives. XON and XOFF
+/// are never passed up to the caller.
+///
+/// When the buffer is three-quarters full an XOFF is sent, and once it has
+/// drained to a quarter full an XON is sent. Data being sent can't contain
+/// XON or XOFF octets, as the other end would take them as flow control.
+pub struct SoftFlowControl<T, const N: usize> {
+    inner: T,
+    buffer: [u8; N],
+    head: usize,
+    len: usize,
+    paused: bool,
+    sent_xoff: bool,
+}
+
+impl<T, const N: usize> SoftFlowControl<T, N>
+    where T: MutBlockingTx + MutNonBlockingRx<Error = <T as MutBlockingTx>::Error>
+{
+    /// The buffer level at which an XOFF is sent.
+    const HIGH_WATER: usize = N - N / 4;
+    /// The buffer level at which an XON is sent, after an XOFF.
+    const LOW_WATER: usize = N / 4;
+
+    const CHECK: () = assert!(N >= 1, "a SoftFlowControl needs room for at least one octet");
+
+    /// Wrap a port. The other end is assumed to be ready to receive.
+    pub fn new(inner: T) -> SoftFlowControl<T, N> {
+        let () = Self::CHECK;
+        SoftFlowControl {
+            inner,
+            buffer: [0; N],
+            head: 0,
+            len: 0,
+            paused: false,
+            sent_xoff: false,
+        }
+    }
+
+    /// Returns true if the other end has sent XOFF, so sending is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Release the wrapped port. Anything in the receive buffer is lost.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Read everything waiting on the port into the buffer, acting on any
+    /// XON or XOFF, and send an XOFF if the buffer is getting full.
+    pub fn poll(&mut self) -> Result<(), FlowControlError<<T as MutBlockingTx>::Error>> {
+        while let Some(ch) = self.inner.getc_try().map_err(FlowControlError::Inner)? {
+            match ch {
+                XON => self.paused = false,
+                XOFF => self.paused = true,
+                _ if self.len == N => return Err(FlowControlError::Overflow),
+                _ => {
+                    self.buffer[(self.head + self.len) % N] = ch;
+                    self.len += 1;
+                }
+            }
+            if !self.sent_xoff && self.len >= Self::HIGH_WATER {
+                self.inner.putc(XOFF).map_err(FlowControlError::Inner)?;
+                self.sent_xoff = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T, const N: usize> MutBlockingTx for SoftFlowControl<T, N>
+    where T: MutBlockingTx + MutNonBlockingRx<Error = <T as MutBlockingTx>::Error>
+{
+    type Error = FlowControlError<<T as MutBlockingTx>::Error>;
+
+    /// Send an octet, first waiting for an XON if the other end has sent
+    /// XOFF.
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.poll()?;
+        while self.paused {
+            self.poll()?;
+        }
+        self.inner.putc(ch).map_err(FlowControlError::Inner)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().map_err(FlowControlError::Inner)
+    }
+}
+
+impl<T, const N: usize> MutBlockingRx for SoftFlowControl<T, N>
+    where T: MutBlockingTx + MutNonBlockingRx<Error = <T as MutBlockingTx>::Error>
+{
+    type Error = FlowControlError<<T as MutBlockingTx>::Error>;
+
+    /// Return the next buffered octet, reading from the port until there is
+    /// one. Sends an XON once the buffer has drained after an XOFF.
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        while self.len == 0 {
+            self.poll()?;
+        }
+        // Send any XON first, so the octet isn't lost if that fails.
+        if self.sent_xoff && self.len - 1 <= Self::LOW_WATER {
+            self.inner.putc(XON).map_err(FlowControlError::Inner)?;
+            self.sent_xoff = false;
+        }
+        let ch = self.buffer[self.head];
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Ok(ch)
+    }
+
+    /// The number of octets in the buffer. Octets still waiting in the port
+    /// aren't counted.
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        Ok(self.len)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
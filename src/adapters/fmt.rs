@@ -0,0 +1,94 @@
+//! Formatted text output, with `write!`.
+
+use core::fmt;
+
+use crate::{ImmutBlockingTx, MutBlockingTx};
+
+/// Wraps a `MutBlockingTx` and implements `core::fmt::Write`, so you can use
+/// `write!` and `writeln!` with it.
+///
+/// `core::fmt::Write` can't be implemented directly for every
+/// `MutBlockingTx`, because of the orphan rules. `fmt::Error` can't carry
+/// the port's error either, so the last one is kept and can be collected
+/// with `take_error`. `SerialExt::puts_fmt` does all this for you.
+///
+/// ```
+/// use std::fmt::Write;
+/// use embedded_serial::adapters::FmtWrite;
+/// use embedded_serial::testing::Loopback;
+///
+/// let mut port = FmtWrite::new(Loopback::<16>::new());
+/// write!(port, "code: {}\r\n", 42).unwrap();
+/// assert!(port.into_inner().rx_buffer().eq(b"code: 42\r\n".iter().cloned()));
+/// ```
+pub struct FmtWrite<T>
+    where T: MutBlockingTx
+{
+    inner: T,
+    error: Option<T::Error>,
+}
+
+impl<T> FmtWrite<T>
+    where T: MutBlockingTx
+{
+    /// Wrap a transmitter.
+    pub fn new(inner: T) -> FmtWrite<T> {
+        FmtWrite { inner, error: None }
+    }
+
+    /// Returns the error from the last failed write, if there has been one
+    /// since this was last called.
+    pub fn take_error(&mut self) -> Option<T::Error> {
+        self.error.take()
+    }
+
+    /// Release the wrapped transmitter.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> fmt::Write for FmtWrite<T>
+    where T: MutBlockingTx
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.puts(s).map_err(|(_, e)| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// Presents an `ImmutBlockingTx` as a `MutBlockingTx`, so it can be
+/// wrapped in a `FmtWrite` by `ImmutSerialExt::puts_fmt`.
+pub(crate) struct ImmutTx<'a, T>(pub(crate) &'a T) where T: ?Sized;
+
+impl<'a, T> MutBlockingTx for ImmutTx<'a, T>
+    where T: ?Sized + ImmutBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.0.putc(ch)
+    }
+
+    fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.0.puts(data)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.0.flush()
+    }
+
+    fn puts_repeat(&mut self, byte: u8, count: usize) -> Result<(), (usize, Self::Error)> {
+        self.0.puts_repeat(byte, count)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,92 @@
+//! Showing transmitted data as a hex dump.
+
+use crate::codec::{self, HEX_DUMP_WIDTH};
+use crate::MutBlockingTx;
+
+/// Wraps a `MutBlockingTx` and, instead of passing on the octets sent to it,
+/// writes them to the wrapped port as a hex dump: an offset, sixteen octets
+/// in hex and the same octets as ASCII on each line, in the same layout as
+/// `SerialExt::puts_hex_dump`.
+///
+/// Octets are collected until a line is full, and the line is written when
+/// the next octet arrives, so a failed write is reported against the octet
+/// which couldn't be accepted. Call `flush_dump` to write out whatever has
+/// been collected so far.
+///
+/// ```
+/// use embedded_serial::MutBlockingTx;
+/// use embedded_serial::adapters::HexDumpFormatter;
+/// use embedded_serial::testing::VecTx;
+///
+/// let mut port = HexDumpFormatter::new(VecTx::<128>::new());
+/// port.puts(b"AT\r\n").unwrap();
+/// port.flush_dump().unwrap();
+/// assert!(port.into_inner().as_slice().starts_with(b"00000000  41 54 0d 0a"));
+/// ```
+pub struct HexDumpFormatter<T> {
+    inner: T,
+    line: [u8; HEX_DUMP_WIDTH],
+    len: usize,
+    offset: usize,
+}
+
+impl<T> HexDumpFormatter<T>
+    where T: MutBlockingTx
+{
+    /// Wrap a transmitter. The dump starts at offset zero.
+    pub fn new(inner: T) -> HexDumpFormatter<T> {
+        HexDumpFormatter {
+            inner,
+            line: [0; HEX_DUMP_WIDTH],
+            len: 0,
+            offset: 0,
+        }
+    }
+
+    /// Write out the line collected so far, even if it isn't full. Does
+    /// nothing if no octets have been collected. If this fails, the octets
+    /// are kept and the whole line is written again next time.
+    pub fn flush_dump(&mut self) -> Result<(), T::Error> {
+        if self.len == 0 {
+            return Ok(());
+        }
+        codec::put_hex_dump_line(&mut self.inner, self.offset, &self.line[..self.len])?;
+        self.offset = self.offset.wrapping_add(self.len);
+        self.len = 0;
+        Ok(())
+    }
+
+    /// Release the wrapped transmitter. Any octets not yet written out by
+    /// `flush_dump` are lost.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingTx for HexDumpFormatter<T>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        if self.len == HEX_DUMP_WIDTH {
+            self.flush_dump()?;
+        }
+        self.line[self.len] = ch;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Write out any partial line with `flush_dump`, then flush the wrapped
+    /// port.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.flush_dump()?;
+        self.inner.flush()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,109 @@
+//! Converting between Unix and network line endings.
+
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// Wraps a `MutBlockingTx` and sends every bare `\n` as `\r\n`, for devices
+/// which need network line endings. A `\n` which already follows a `\r` is
+/// sent unchanged.
+pub struct LineEndingNormalizerTx<T> {
+    inner: T,
+    last_was_cr: bool,
+}
+
+impl<T> LineEndingNormalizerTx<T>
+    where T: MutBlockingTx
+{
+    /// Wrap a transmitter.
+    pub fn new(inner: T) -> LineEndingNormalizerTx<T> {
+        LineEndingNormalizerTx {
+            inner,
+            last_was_cr: false,
+        }
+    }
+
+    /// Release the wrapped transmitter.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingTx for LineEndingNormalizerTx<T>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        if ch == b'\n' && !self.last_was_cr {
+            self.inner.putc(b'\r')?;
+            // If the `\n` fails, don't send another `\r` when it's retried.
+            self.last_was_cr = true;
+        }
+        self.inner.putc(ch)?;
+        self.last_was_cr = ch == b'\r';
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+/// Wraps a `MutBlockingRx` and turns every `\r\n` received into a single
+/// `\n`, so the layer above only sees Unix line endings.
+///
+/// A `\r` can't be handed out until the next octet has arrived, as that
+/// decides whether it is the start of a `\r\n`. Any other octet after a `\r`
+/// is kept and returned by the next `getc`.
+pub struct LineEndingNormalizerRx<T> {
+    inner: T,
+    pending: Option<u8>,
+}
+
+impl<T> LineEndingNormalizerRx<T>
+    where T: MutBlockingRx
+{
+    /// Wrap a receiver.
+    pub fn new(inner: T) -> LineEndingNormalizerRx<T> {
+        LineEndingNormalizerRx {
+            inner,
+            pending: None,
+        }
+    }
+
+    /// Release the wrapped receiver. Any octet read but not yet returned is
+    /// returned too.
+    pub fn into_inner(self) -> (T, Option<u8>) {
+        (self.inner, self.pending)
+    }
+}
+
+impl<T> MutBlockingRx for LineEndingNormalizerRx<T>
+    where T: MutBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        let ch = match self.pending.take() {
+            Some(ch) => ch,
+            None => self.inner.getc()?,
+        };
+        if ch == b'\r' {
+            match self.inner.getc() {
+                Ok(b'\n') => return Ok(b'\n'),
+                Ok(next) => self.pending = Some(next),
+                Err(e) => {
+                    // Keep the `\r` so it isn't lost.
+                    self.pending = Some(ch);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(ch)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
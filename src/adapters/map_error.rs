@@ -0,0 +1,442 @@
+//! Converting a port's error type.
+
+use crate::{ImmutBlockingRx, ImmutBlockingRxWithTimeout, ImmutBlockingTx,
+            ImmutBlockingTxWithTimeout, ImmutNonBlockingRx, ImmutNonBlockingRxWithTimeout,
+            ImmutNonBlockingTx, ImmutNonBlockingTxWithTimeout, MutBlockingRx,
+            MutBlockingRxWithTimeout, MutBlockingTx, MutBlockingTxWithTimeout, MutNonBlockingRx,
+            MutNonBlockingRxWithTimeout, MutNonBlockingTx, RxReady};
+
+/// Wraps a port and passes every error it returns through `f`, so layers
+/// with different error types can be given a common one.
+///
+/// It implements whichever of the serial traits the wrapped port does, with
+/// `Error` set to whatever `f` returns. Everything else is passed straight
+/// through.
+///
+/// ```
+/// use embedded_serial::MutBlockingTx;
+/// use embedded_serial::adapters::MapError;
+/// use embedded_serial::testing::{VecTx, VecTxError};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum AppError {
+///     LogFull,
+/// }
+///
+/// let mut log = MapError::new(VecTx::<2>::new(), |_: VecTxError| AppError::LogFull);
+/// assert_eq!(log.puts(b"abc"), Err((2, AppError::LogFull)));
+/// ```
+pub struct MapError<T, F> {
+    inner: T,
+    f: F,
+}
+
+impl<T, F> MapError<T, F> {
+    /// Wrap a port, converting its errors with `f`.
+    pub fn new(inner: T, f: F) -> MapError<T, F> {
+        MapError { inner, f }
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, F, E> MutBlockingTx for MapError<T, F>
+    where T: MutBlockingTx,
+          F: Fn(T::Error) -> E
+{
+    type Error = E;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.inner.putc(ch).map_err(&self.f)
+    }
+
+    fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.inner.puts(data).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().map_err(&self.f)
+    }
+
+    fn puts_repeat(&mut self, byte: u8, count: usize) -> Result<(), (usize, Self::Error)> {
+        self.inner.puts_repeat(byte, count).map_err(|(sent, e)| (sent, (self.f)(e)))
+    }
+}
+
+impl<T, F, E> MutBlockingTxWithTimeout for MapError<T, F>
+    where T: MutBlockingTxWithTimeout,
+          F: Fn(T::Error) -> E
+{
+    type Timeout = T::Timeout;
+    type Error = E;
+
+    fn putc_wait(&mut self, ch: u8, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        self.inner.putc_wait(ch, timeout).map_err(&self.f)
+    }
+
+    fn puts_wait<I>(&mut self,
+                    data: &I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.inner.puts_wait(data, timeout).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn flush_wait(&mut self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        self.inner.flush_wait(timeout).map_err(&self.f)
+    }
+
+    fn puts_repeat_wait(&mut self,
+                        byte: u8,
+                        count: usize,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)> {
+        self.inner.puts_repeat_wait(byte, count, timeout).map_err(|(sent, e)| (sent, (self.f)(e)))
+    }
+}
+
+impl<T, F, E> MutNonBlockingTx for MapError<T, F>
+    where T: MutNonBlockingTx,
+          F: Fn(T::Error) -> E
+{
+    type Error = E;
+
+    fn putc_try(&mut self, ch: u8) -> Result<Option<u8>, Self::Error> {
+        self.inner.putc_try(ch).map_err(&self.f)
+    }
+
+    fn puts_try<I>(&mut self, data: &I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.inner.puts_try(data).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().map_err(&self.f)
+    }
+
+    fn puts_repeat_try(&mut self, byte: u8, count: usize) -> Result<usize, (usize, Self::Error)> {
+        self.inner.puts_repeat_try(byte, count).map_err(|(sent, e)| (sent, (self.f)(e)))
+    }
+}
+
+impl<T, F, E> MutBlockingRx for MapError<T, F>
+    where T: MutBlockingRx,
+          F: Fn(T::Error) -> E
+{
+    type Error = E;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        self.inner.getc().map_err(&self.f)
+    }
+
+    fn gets<I>(&mut self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.inner.gets(buffer).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available().map_err(&self.f)
+    }
+}
+
+impl<T, F, E> MutBlockingRxWithTimeout for MapError<T, F>
+    where T: MutBlockingRxWithTimeout,
+          F: Fn(T::Error) -> E
+{
+    type Timeout = T::Timeout;
+    type Error = E;
+
+    fn getc_wait(&mut self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        self.inner.getc_wait(timeout).map_err(&self.f)
+    }
+
+    fn gets_wait<I>(&mut self,
+                    buffer: &mut I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.inner.gets_wait(buffer, timeout).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available().map_err(&self.f)
+    }
+}
+
+impl<T, F, E> RxReady for MapError<T, F>
+    where T: RxReady,
+          F: Fn(T::Error) -> E
+{
+    fn wait_for_rx_ready(&mut self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        self.inner.wait_for_rx_ready(timeout).map_err(&self.f)
+    }
+}
+
+impl<T, F, E> MutNonBlockingRx for MapError<T, F>
+    where T: MutNonBlockingRx,
+          F: Fn(T::Error) -> E
+{
+    type Error = E;
+
+    fn getc_try(&mut self) -> Result<Option<u8>, Self::Error> {
+        self.inner.getc_try().map_err(&self.f)
+    }
+
+    fn gets_try<I>(&mut self, buffer: &mut I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.inner.gets_try(buffer).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available().map_err(&self.f)
+    }
+}
+
+impl<T, F, E> MutNonBlockingRxWithTimeout for MapError<T, F>
+    where T: MutNonBlockingRxWithTimeout,
+          F: Fn(T::Error) -> E
+{
+    type Timeout = T::Timeout;
+    type Error = E;
+
+    fn getc_try_wait(&mut self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        self.inner.getc_try_wait(timeout).map_err(&self.f)
+    }
+
+    fn gets_try_wait<I>(&mut self,
+                        buffer: &mut I,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.inner.gets_try_wait(buffer, timeout).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available().map_err(&self.f)
+    }
+}
+
+impl<T, F, E> ImmutBlockingTx for MapError<T, F>
+    where T: ImmutBlockingTx,
+          F: Fn(T::Error) -> E
+{
+    type Error = E;
+
+    fn putc(&self, ch: u8) -> Result<(), Self::Error> {
+        self.inner.putc(ch).map_err(&self.f)
+    }
+
+    fn puts<I>(&self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.inner.puts(data).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        self.inner.flush().map_err(&self.f)
+    }
+
+    fn puts_repeat(&self, byte: u8, count: usize) -> Result<(), (usize, Self::Error)> {
+        self.inner.puts_repeat(byte, count).map_err(|(sent, e)| (sent, (self.f)(e)))
+    }
+}
+
+impl<T, F, E> ImmutBlockingTxWithTimeout for MapError<T, F>
+    where T: ImmutBlockingTxWithTimeout,
+          F: Fn(T::Error) -> E
+{
+    type Timeout = T::Timeout;
+    type Error = E;
+
+    fn putc_wait(&self, ch: u8, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        self.inner.putc_wait(ch, timeout).map_err(&self.f)
+    }
+
+    fn puts_wait<I>(&self,
+                    data: &I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.inner.puts_wait(data, timeout).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn flush_wait(&self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        self.inner.flush_wait(timeout).map_err(&self.f)
+    }
+
+    fn puts_repeat_wait(&self,
+                        byte: u8,
+                        count: usize,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)> {
+        self.inner.puts_repeat_wait(byte, count, timeout).map_err(|(sent, e)| (sent, (self.f)(e)))
+    }
+}
+
+impl<T, F, E> ImmutNonBlockingTx for MapError<T, F>
+    where T: ImmutNonBlockingTx,
+          F: Fn(T::Error) -> E
+{
+    type Error = E;
+
+    fn putc_try(&self, ch: u8) -> Result<Option<u8>, Self::Error> {
+        self.inner.putc_try(ch).map_err(&self.f)
+    }
+
+    fn puts_try<I>(&self, data: &I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.inner.puts_try(data).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        self.inner.flush().map_err(&self.f)
+    }
+
+    fn puts_repeat_try(&self, byte: u8, count: usize) -> Result<usize, (usize, Self::Error)> {
+        self.inner.puts_repeat_try(byte, count).map_err(|(sent, e)| (sent, (self.f)(e)))
+    }
+}
+
+impl<T, F, E> ImmutNonBlockingTxWithTimeout for MapError<T, F>
+    where T: ImmutNonBlockingTxWithTimeout,
+          F: Fn(T::Error) -> E
+{
+    type Timeout = T::Timeout;
+    type Error = E;
+
+    fn putc_try_wait(&self, ch: u8, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        self.inner.putc_try_wait(ch, timeout).map_err(&self.f)
+    }
+
+    fn puts_try_wait<I>(&self,
+                        data: &I,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.inner.puts_try_wait(data, timeout).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn flush_wait(&self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        self.inner.flush_wait(timeout).map_err(&self.f)
+    }
+
+    fn puts_repeat_try_wait(&self,
+                            byte: u8,
+                            count: usize,
+                            timeout: &Self::Timeout)
+                            -> Result<usize, (usize, Self::Error)> {
+        self.inner.puts_repeat_try_wait(byte, count, timeout).map_err(|(sent, e)| (sent, (self.f)(e)))
+    }
+}
+
+impl<T, F, E> ImmutBlockingRx for MapError<T, F>
+    where T: ImmutBlockingRx,
+          F: Fn(T::Error) -> E
+{
+    type Error = E;
+
+    fn getc(&self) -> Result<u8, Self::Error> {
+        self.inner.getc().map_err(&self.f)
+    }
+
+    fn gets<I>(&self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.inner.gets(buffer).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available().map_err(&self.f)
+    }
+}
+
+impl<T, F, E> ImmutBlockingRxWithTimeout for MapError<T, F>
+    where T: ImmutBlockingRxWithTimeout,
+          F: Fn(T::Error) -> E
+{
+    type Timeout = T::Timeout;
+    type Error = E;
+
+    fn getc_wait(&self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        self.inner.getc_wait(timeout).map_err(&self.f)
+    }
+
+    fn gets_wait<I>(&self,
+                    buffer: &mut I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.inner.gets_wait(buffer, timeout).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available().map_err(&self.f)
+    }
+}
+
+impl<T, F, E> ImmutNonBlockingRx for MapError<T, F>
+    where T: ImmutNonBlockingRx,
+          F: Fn(T::Error) -> E
+{
+    type Error = E;
+
+    fn getc_try(&self) -> Result<Option<u8>, Self::Error> {
+        self.inner.getc_try().map_err(&self.f)
+    }
+
+    fn gets_try<I>(&self, buffer: &mut I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.inner.gets_try(buffer).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available().map_err(&self.f)
+    }
+}
+
+impl<T, F, E> ImmutNonBlockingRxWithTimeout for MapError<T, F>
+    where T: ImmutNonBlockingRxWithTimeout,
+          F: Fn(T::Error) -> E
+{
+    type Timeout = T::Timeout;
+    type Error = E;
+
+    fn getc_try_wait(&self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        self.inner.getc_try_wait(timeout).map_err(&self.f)
+    }
+
+    fn gets_try_wait<I>(&self,
+                        buffer: &mut I,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.inner.gets_try_wait(buffer, timeout).map_err(|(count, e)| (count, (self.f)(e)))
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available().map_err(&self.f)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
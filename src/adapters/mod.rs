@@ -0,0 +1,55 @@
+//! # Adapters
+//!
+//! Wrappers which take something implementing one of the serial traits and
+//! implement a serial trait themselves, adding some behaviour on the way
+//! through.
+
+mod base64;
+mod chunked;
+mod deadline;
+mod divider;
+mod echo;
+mod fallover;
+mod flow;
+mod fmt;
+mod hexdump;
+mod line_ending;
+mod map_error;
+mod partial;
+mod peekable;
+mod recording;
+mod retrier;
+mod shared;
+mod spin;
+mod stats;
+mod tee;
+mod timeout;
+
+pub use self::base64::{Base64Rx, Base64Tx};
+pub use self::chunked::ChunkedTx;
+pub use self::deadline::{DeadlineError, GlobalTimeout};
+pub use self::divider::FrequencyDivider;
+pub use self::echo::EchoSerial;
+pub use self::fallover::{ActivePort, FalloverError, FalloverSerial};
+pub use self::flow::{FlowControlError, SoftFlowControl};
+pub use self::fmt::FmtWrite;
+pub(crate) use self::fmt::ImmutTx;
+pub use self::hexdump::HexDumpFormatter;
+pub use self::line_ending::{LineEndingNormalizerRx, LineEndingNormalizerTx};
+pub use self::map_error::MapError;
+pub use self::partial::{PartialWriteDetector, PartialWriteError};
+pub use self::peekable::PeekableRx;
+pub use self::recording::RecordingSerial;
+pub use self::retrier::RetrierTx;
+pub use self::shared::SharedSerial;
+pub use self::spin::{SpinBlockingRx, SpinBlockingRxWithTimeout, SpinBlockingTx,
+                     SpinBlockingTxWithTimeout};
+pub use self::stats::{SerialStats, StatisticsCollector};
+pub use self::tee::{ImmutTee, Tee, TeeError};
+pub use self::timeout::TimeoutAdapter;
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,86 @@
+//! Detection of partial writes.
+
+use crate::MutBlockingTx;
+
+/// Wraps a `MutBlockingTx` and makes sure partial writes can't go unnoticed.
+///
+/// Calls to `puts` are passed to the wrapped transmitter's `puts`. If that
+/// fails part way through, a debug build panics. A release build returns a
+/// `PartialWriteError` recording how much of the data actually made it out.
+pub struct PartialWriteDetector<T> {
+    inner: T,
+}
+
+/// The error returned by `PartialWriteDetector` when a write fails.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PartialWriteError<E> {
+    /// The number of octets which were sent.
+    pub sent: usize,
+    /// The number of octets which should have been sent.
+    pub expected: usize,
+    /// The error from the wrapped transmitter.
+    pub error: E,
+}
+
+impl<T> PartialWriteDetector<T>
+    where T: MutBlockingTx
+{
+    /// Wrap a transmitter.
+    pub fn new(inner: T) -> PartialWriteDetector<T> {
+        PartialWriteDetector { inner }
+    }
+
+    /// Release the wrapped transmitter.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingTx for PartialWriteDetector<T>
+    where T: MutBlockingTx
+{
+    type Error = PartialWriteError<T::Error>;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.inner.putc(ch).map_err(|e| {
+            PartialWriteError {
+                sent: 0,
+                expected: 1,
+                error: e,
+            }
+        })
+    }
+
+    fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let data = data.as_ref();
+        self.inner.puts(data).map_err(|(sent, e)| {
+            if cfg!(debug_assertions) && sent < data.len() {
+                panic!("partial write: sent {} of {} octets", sent, data.len());
+            }
+            let error = PartialWriteError {
+                sent,
+                expected: data.len(),
+                error: e,
+            };
+            (sent, error)
+        })
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().map_err(|e| {
+            PartialWriteError {
+                sent: 0,
+                expected: 0,
+                error: e,
+            }
+        })
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
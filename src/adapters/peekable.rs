@@ -0,0 +1,63 @@
+//! Checking for received data without losing it.
+
+use crate::MutNonBlockingRx;
+
+/// Wraps a `MutNonBlockingRx` so you can ask whether data is available
+/// without losing it.
+///
+/// `is_data_available` has to read an octet to find out, so that octet is
+/// held here and handed out by the next `getc_try`.
+pub struct PeekableRx<T> {
+    inner: T,
+    peeked: Option<u8>,
+}
+
+impl<T> PeekableRx<T>
+    where T: MutNonBlockingRx
+{
+    /// Wrap a receiver.
+    pub fn new(inner: T) -> PeekableRx<T> {
+        PeekableRx { inner, peeked: None }
+    }
+
+    /// Returns true if an octet can be read right now. At most one call is
+    /// made to the inner `getc_try`, and anything it returns is kept for the
+    /// next read.
+    pub fn is_data_available(&mut self) -> Result<bool, T::Error> {
+        if self.peeked.is_none() {
+            self.peeked = self.inner.getc_try()?;
+        }
+        Ok(self.peeked.is_some())
+    }
+
+    /// Release the wrapped receiver. Any octet held by `is_data_available` is
+    /// returned too.
+    pub fn into_inner(self) -> (T, Option<u8>) {
+        (self.inner, self.peeked)
+    }
+}
+
+impl<T> MutNonBlockingRx for PeekableRx<T>
+    where T: MutNonBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc_try(&mut self) -> Result<Option<u8>, Self::Error> {
+        match self.peeked.take() {
+            Some(ch) => Ok(Some(ch)),
+            None => self.inner.getc_try(),
+        }
+    }
+
+    /// Includes the octet kept back by `is_data_available`, if any.
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        let waiting = self.inner.bytes_available()?;
+        Ok(waiting + usize::from(self.peeked.is_some()))
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
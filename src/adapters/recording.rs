@@ -0,0 +1,351 @@
+//! Recording all the traffic through a port.
+
+use crate::{MutBlockingRx, MutBlockingRxWithTimeout, MutBlockingTx, MutBlockingTxWithTimeout,
+            MutNonBlockingRx, MutNonBlockingRxWithTimeout, MutNonBlockingTx, RxReady};
+
+/// A fixed-capacity log of octets, which keeps the first `N` it is given.
+struct Log<const N: usize> {
+    data: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Log<N> {
+    fn new() -> Log<N> {
+        Log {
+            data: [0; N],
+            len: 0,
+        }
+    }
+
+    fn record(&mut self, octets: &[u8]) {
+        let space = &mut self.data[self.len..];
+        let count = core::cmp::min(space.len(), octets.len());
+        space[..count].copy_from_slice(&octets[..count]);
+        self.len += count;
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+/// Wraps a port and keeps a copy of every octet sent and received, for
+/// inspection later.
+///
+/// The first `TX_N` octets sent and the first `RX_N` octets received are
+/// kept; anything after that is passed through but not recorded. Only
+/// octets which were actually transferred are recorded.
+///
+/// All the `Mut*` traits that the wrapped port implements are implemented.
+pub struct RecordingSerial<T, const TX_N: usize, const RX_N: usize> {
+    inner: T,
+    tx: Log<TX_N>,
+    rx: Log<RX_N>,
+}
+
+impl<T, const TX_N: usize, const RX_N: usize> RecordingSerial<T, TX_N, RX_N> {
+    /// Wrap a port, with empty logs.
+    pub fn new(inner: T) -> RecordingSerial<T, TX_N, RX_N> {
+        RecordingSerial {
+            inner,
+            tx: Log::new(),
+            rx: Log::new(),
+        }
+    }
+
+    /// The octets sent so far.
+    pub fn tx_log(&self) -> &[u8] {
+        self.tx.as_slice()
+    }
+
+    /// The octets received so far.
+    pub fn rx_log(&self) -> &[u8] {
+        self.rx.as_slice()
+    }
+
+    /// Empty both logs.
+    pub fn clear_logs(&mut self) {
+        self.tx.len = 0;
+        self.rx.len = 0;
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, const TX_N: usize, const RX_N: usize> MutBlockingTx for RecordingSerial<T, TX_N, RX_N>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.inner.putc(ch)?;
+        self.tx.record(&[ch]);
+        Ok(())
+    }
+
+    fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let data = data.as_ref();
+        let result = self.inner.puts(data);
+        let sent = match result {
+            Ok(()) => data.len(),
+            Err((sent, _)) => sent,
+        };
+        self.tx.record(&data[..sent]);
+        result
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+
+    fn puts_repeat(&mut self, byte: u8, count: usize) -> Result<(), (usize, Self::Error)> {
+        let result = self.inner.puts_repeat(byte, count);
+        let sent = match result {
+            Ok(()) => count,
+            Err((sent, _)) => sent,
+        };
+        for _ in 0..sent {
+            self.tx.record(&[byte]);
+        }
+        result
+    }
+}
+
+impl<T, const TX_N: usize, const RX_N: usize> MutBlockingTxWithTimeout
+    for RecordingSerial<T, TX_N, RX_N>
+    where T: MutBlockingTxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn putc_wait(&mut self, ch: u8, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.putc_wait(ch, timeout)?;
+        if result.is_some() {
+            self.tx.record(&[ch]);
+        }
+        Ok(result)
+    }
+
+    fn puts_wait<I>(&mut self,
+                    data: &I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let data = data.as_ref();
+        let result = self.inner.puts_wait(data, timeout);
+        let sent = match result {
+            Ok(sent) | Err((sent, _)) => sent,
+        };
+        self.tx.record(&data[..sent]);
+        result
+    }
+
+    fn flush_wait(&mut self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        self.inner.flush_wait(timeout)
+    }
+
+    fn puts_repeat_wait(&mut self,
+                        byte: u8,
+                        count: usize,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)> {
+        let result = self.inner.puts_repeat_wait(byte, count, timeout);
+        let sent = match result {
+            Ok(sent) | Err((sent, _)) => sent,
+        };
+        for _ in 0..sent {
+            self.tx.record(&[byte]);
+        }
+        result
+    }
+}
+
+impl<T, const TX_N: usize, const RX_N: usize> MutNonBlockingTx for RecordingSerial<T, TX_N, RX_N>
+    where T: MutNonBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc_try(&mut self, ch: u8) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.putc_try(ch)?;
+        if result.is_some() {
+            self.tx.record(&[ch]);
+        }
+        Ok(result)
+    }
+
+    fn puts_try<I>(&mut self, data: &I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let data = data.as_ref();
+        let result = self.inner.puts_try(data);
+        let sent = match result {
+            Ok(sent) | Err((sent, _)) => sent,
+        };
+        self.tx.record(&data[..sent]);
+        result
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+
+    fn puts_repeat_try(&mut self, byte: u8, count: usize) -> Result<usize, (usize, Self::Error)> {
+        let result = self.inner.puts_repeat_try(byte, count);
+        let sent = match result {
+            Ok(sent) | Err((sent, _)) => sent,
+        };
+        for _ in 0..sent {
+            self.tx.record(&[byte]);
+        }
+        result
+    }
+}
+
+impl<T, const TX_N: usize, const RX_N: usize> MutBlockingRx for RecordingSerial<T, TX_N, RX_N>
+    where T: MutBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        let ch = self.inner.getc()?;
+        self.rx.record(&[ch]);
+        Ok(ch)
+    }
+
+    fn gets<I>(&mut self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        let buffer = buffer.as_mut();
+        let result = self.inner.gets(buffer);
+        let received = match result {
+            Ok(()) => buffer.len(),
+            Err((received, _)) => received,
+        };
+        self.rx.record(&buffer[..received]);
+        result
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+impl<T, const TX_N: usize, const RX_N: usize> MutBlockingRxWithTimeout
+    for RecordingSerial<T, TX_N, RX_N>
+    where T: MutBlockingRxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn getc_wait(&mut self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.getc_wait(timeout)?;
+        if let Some(ch) = result {
+            self.rx.record(&[ch]);
+        }
+        Ok(result)
+    }
+
+    fn gets_wait<I>(&mut self,
+                    buffer: &mut I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        let buffer = buffer.as_mut();
+        let result = self.inner.gets_wait(buffer, timeout);
+        let received = match result {
+            Ok(received) | Err((received, _)) => received,
+        };
+        self.rx.record(&buffer[..received]);
+        result
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+impl<T, const TX_N: usize, const RX_N: usize> RxReady for RecordingSerial<T, TX_N, RX_N>
+    where T: RxReady
+{
+    fn wait_for_rx_ready(&mut self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        self.inner.wait_for_rx_ready(timeout)
+    }
+}
+
+impl<T, const TX_N: usize, const RX_N: usize> MutNonBlockingRx for RecordingSerial<T, TX_N, RX_N>
+    where T: MutNonBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc_try(&mut self) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.getc_try()?;
+        if let Some(ch) = result {
+            self.rx.record(&[ch]);
+        }
+        Ok(result)
+    }
+
+    fn gets_try<I>(&mut self, buffer: &mut I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        let buffer = buffer.as_mut();
+        let result = self.inner.gets_try(buffer);
+        let received = match result {
+            Ok(received) | Err((received, _)) => received,
+        };
+        self.rx.record(&buffer[..received]);
+        result
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+impl<T, const TX_N: usize, const RX_N: usize> MutNonBlockingRxWithTimeout
+    for RecordingSerial<T, TX_N, RX_N>
+    where T: MutNonBlockingRxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn getc_try_wait(&mut self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.getc_try_wait(timeout)?;
+        if let Some(ch) = result {
+            self.rx.record(&[ch]);
+        }
+        Ok(result)
+    }
+
+    fn gets_try_wait<I>(&mut self,
+                        buffer: &mut I,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        let buffer = buffer.as_mut();
+        let result = self.inner.gets_try_wait(buffer, timeout);
+        let received = match result {
+            Ok(received) | Err((received, _)) => received,
+        };
+        self.rx.record(&buffer[..received]);
+        result
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
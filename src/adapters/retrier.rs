@@ -0,0 +1,109 @@
+//! Retrying failed sends.
+
+use crate::error::RetriableError;
+use crate::MutBlockingTx;
+
+/// The default delay between retries - none at all.
+fn no_delay() {}
+
+/// Wraps a `MutBlockingTx` and re-sends any octet which fails with a
+/// retriable error (see `RetriableError`), up to `MAX_RETRIES` times,
+/// calling `delay` before each retry.
+///
+/// If every retry fails, or a retry fails with an error which isn't
+/// retriable, the error from the first attempt is returned.
+pub struct RetrierTx<T, const MAX_RETRIES: usize, F = fn()> {
+    inner: T,
+    delay: F,
+    last_retry_count: usize,
+}
+
+impl<T, const MAX_RETRIES: usize> RetrierTx<T, MAX_RETRIES>
+    where T: MutBlockingTx,
+          T::Error: RetriableError
+{
+    /// Wrap a transmitter, retrying straight away with no delay.
+    pub fn new(inner: T) -> RetrierTx<T, MAX_RETRIES> {
+        RetrierTx::with_delay(inner, no_delay)
+    }
+}
+
+impl<T, const MAX_RETRIES: usize, F> RetrierTx<T, MAX_RETRIES, F>
+    where T: MutBlockingTx,
+          T::Error: RetriableError,
+          F: Fn()
+{
+    /// Wrap a transmitter, calling `delay` before each retry.
+    pub fn with_delay(inner: T, delay: F) -> RetrierTx<T, MAX_RETRIES, F> {
+        RetrierTx {
+            inner,
+            delay,
+            last_retry_count: 0,
+        }
+    }
+
+    /// The number of retries made by the most recent `putc` or `puts` call
+    /// (in total, across all the octets of a `puts`).
+    pub fn last_retry_count(&self) -> usize {
+        self.last_retry_count
+    }
+
+    /// Release the wrapped transmitter.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Send one octet, retrying as needed, and add the retries made to
+    /// `last_retry_count`.
+    fn send(&mut self, ch: u8) -> Result<(), T::Error> {
+        let first = match self.inner.putc(ch) {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+        if first.is_retriable() {
+            for _ in 0..MAX_RETRIES {
+                (self.delay)();
+                self.last_retry_count += 1;
+                match self.inner.putc(ch) {
+                    Ok(()) => return Ok(()),
+                    Err(e) if e.is_retriable() => {}
+                    Err(_) => break,
+                }
+            }
+        }
+        Err(first)
+    }
+}
+
+impl<T, const MAX_RETRIES: usize, F> MutBlockingTx for RetrierTx<T, MAX_RETRIES, F>
+    where T: MutBlockingTx,
+          T::Error: RetriableError,
+          F: Fn()
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.last_retry_count = 0;
+        self.send(ch)
+    }
+
+    fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.last_retry_count = 0;
+        for (count, octet) in data.as_ref().iter().enumerate() {
+            self.send(*octet).map_err(|e| (count, e))?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,223 @@
+//! Sharing a port between several owners on a single thread.
+
+use core::cell::{BorrowMutError, RefCell, RefMut};
+
+use crate::{ImmutBlockingRx, ImmutBlockingRxWithTimeout, ImmutBlockingTx,
+            ImmutBlockingTxWithTimeout, ImmutNonBlockingRx, ImmutNonBlockingRxWithTimeout,
+            ImmutNonBlockingTx, MutBlockingRx, MutBlockingRxWithTimeout, MutBlockingTx,
+            MutBlockingTxWithTimeout, MutNonBlockingRx, MutNonBlockingRxWithTimeout,
+            MutNonBlockingTx};
+
+/// Wraps a port in a `RefCell`, so it can be used through shared
+/// references. Each `Mut*` trait the port implements becomes the matching
+/// `Immut*` trait.
+///
+/// This is for single-threaded code, where a `Mutex` would be overkill. The
+/// port is borrowed for the length of each call, and a call made while it
+/// is already borrowed (e.g. from inside another call) panics. Use
+/// `try_borrow_mut` to find out instead.
+///
+/// ```
+/// use embedded_serial::ImmutBlockingTx;
+/// use embedded_serial::adapters::SharedSerial;
+/// use embedded_serial::testing::VecTx;
+///
+/// let port = SharedSerial::new(VecTx::<16>::new());
+/// let (log, console) = (&port, &port);
+/// log.puts(b"log ").unwrap();
+/// console.puts(b"console").unwrap();
+/// assert_eq!(port.into_inner().as_slice(), b"log console");
+/// ```
+pub struct SharedSerial<T>(RefCell<T>);
+
+impl<T> SharedSerial<T> {
+    /// Wrap a port.
+    pub fn new(inner: T) -> SharedSerial<T> {
+        SharedSerial(RefCell::new(inner))
+    }
+
+    /// Borrow the port, failing instead of panicking if it is already
+    /// borrowed.
+    pub fn try_borrow_mut(&self) -> Result<RefMut<'_, T>, BorrowMutError> {
+        self.0.try_borrow_mut()
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+}
+
+impl<T> ImmutBlockingTx for SharedSerial<T>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&self, ch: u8) -> Result<(), Self::Error> {
+        self.0.borrow_mut().putc(ch)
+    }
+
+    fn puts<I>(&self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.0.borrow_mut().puts(data)
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        self.0.borrow_mut().flush()
+    }
+
+    fn puts_repeat(&self, byte: u8, count: usize) -> Result<(), (usize, Self::Error)> {
+        self.0.borrow_mut().puts_repeat(byte, count)
+    }
+}
+
+impl<T> ImmutBlockingTxWithTimeout for SharedSerial<T>
+    where T: MutBlockingTxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn putc_wait(&self, ch: u8, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        self.0.borrow_mut().putc_wait(ch, timeout)
+    }
+
+    fn puts_wait<I>(&self,
+                    data: &I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.0.borrow_mut().puts_wait(data, timeout)
+    }
+
+    fn flush_wait(&self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        self.0.borrow_mut().flush_wait(timeout)
+    }
+
+    fn puts_repeat_wait(&self,
+                        byte: u8,
+                        count: usize,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)> {
+        self.0.borrow_mut().puts_repeat_wait(byte, count, timeout)
+    }
+}
+
+impl<T> ImmutNonBlockingTx for SharedSerial<T>
+    where T: MutNonBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc_try(&self, ch: u8) -> Result<Option<u8>, Self::Error> {
+        self.0.borrow_mut().putc_try(ch)
+    }
+
+    fn puts_try<I>(&self, data: &I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.0.borrow_mut().puts_try(data)
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        self.0.borrow_mut().flush()
+    }
+
+    fn puts_repeat_try(&self, byte: u8, count: usize) -> Result<usize, (usize, Self::Error)> {
+        self.0.borrow_mut().puts_repeat_try(byte, count)
+    }
+}
+
+impl<T> ImmutBlockingRx for SharedSerial<T>
+    where T: MutBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc(&self) -> Result<u8, Self::Error> {
+        self.0.borrow_mut().getc()
+    }
+
+    fn gets<I>(&self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.0.borrow_mut().gets(buffer)
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        self.0.borrow_mut().bytes_available()
+    }
+}
+
+impl<T> ImmutBlockingRxWithTimeout for SharedSerial<T>
+    where T: MutBlockingRxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn getc_wait(&self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        self.0.borrow_mut().getc_wait(timeout)
+    }
+
+    fn gets_wait<I>(&self,
+                    buffer: &mut I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.0.borrow_mut().gets_wait(buffer, timeout)
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        self.0.borrow_mut().bytes_available()
+    }
+}
+
+impl<T> ImmutNonBlockingRx for SharedSerial<T>
+    where T: MutNonBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc_try(&self) -> Result<Option<u8>, Self::Error> {
+        self.0.borrow_mut().getc_try()
+    }
+
+    fn gets_try<I>(&self, buffer: &mut I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.0.borrow_mut().gets_try(buffer)
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        self.0.borrow_mut().bytes_available()
+    }
+}
+
+impl<T> ImmutNonBlockingRxWithTimeout for SharedSerial<T>
+    where T: MutNonBlockingRxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn getc_try_wait(&self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        self.0.borrow_mut().getc_try_wait(timeout)
+    }
+
+    fn gets_try_wait<I>(&self,
+                        buffer: &mut I,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.0.borrow_mut().gets_try_wait(buffer, timeout)
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        self.0.borrow_mut().bytes_available()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,227 @@
+//! Blocking ports built by spinning on non-blocking ones.
+
+use core::hint::spin_loop;
+
+use crate::{ImmutBlockingRx, ImmutBlockingTx, ImmutNonBlockingRx, ImmutNonBlockingTx,
+            MutBlockingRx, MutBlockingRxWithTimeout, MutBlockingTx, MutBlockingTxWithTimeout,
+            MutNonBlockingRx, MutNonBlockingTx};
+
+/// Wraps a `MutNonBlockingTx` (or `ImmutNonBlockingTx`) and implements
+/// `MutBlockingTx` (or `ImmutBlockingTx`) by calling `putc_try` until the
+/// octet is accepted.
+pub struct SpinBlockingTx<T> {
+    inner: T,
+}
+
+impl<T> SpinBlockingTx<T> {
+    /// Wrap a transmitter.
+    pub fn new(inner: T) -> SpinBlockingTx<T> {
+        SpinBlockingTx { inner }
+    }
+
+    /// Release the wrapped transmitter.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingTx for SpinBlockingTx<T>
+    where T: MutNonBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        while self.inner.putc_try(ch)?.is_none() {
+            spin_loop();
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+impl<T> ImmutBlockingTx for SpinBlockingTx<T>
+    where T: ImmutNonBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&self, ch: u8) -> Result<(), Self::Error> {
+        while self.inner.putc_try(ch)?.is_none() {
+            spin_loop();
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+/// Wraps a `MutNonBlockingRx` (or `ImmutNonBlockingRx`) and implements
+/// `MutBlockingRx` (or `ImmutBlockingRx`) by calling `getc_try` until an
+/// octet arrives.
+pub struct SpinBlockingRx<T> {
+    inner: T,
+}
+
+impl<T> SpinBlockingRx<T> {
+    /// Wrap a receiver.
+    pub fn new(inner: T) -> SpinBlockingRx<T> {
+        SpinBlockingRx { inner }
+    }
+
+    /// Release the wrapped receiver.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingRx for SpinBlockingRx<T>
+    where T: MutNonBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        loop {
+            if let Some(ch) = self.inner.getc_try()? {
+                return Ok(ch);
+            }
+            spin_loop();
+        }
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+impl<T> ImmutBlockingRx for SpinBlockingRx<T>
+    where T: ImmutNonBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc(&self) -> Result<u8, Self::Error> {
+        loop {
+            if let Some(ch) = self.inner.getc_try()? {
+                return Ok(ch);
+            }
+            spin_loop();
+        }
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+/// Wraps a `MutNonBlockingTx` and implements `MutBlockingTxWithTimeout` by
+/// calling `putc_try` until the octet is accepted or `deadline_exceeded`
+/// returns true.
+///
+/// The deadline is whatever the closure says it is, so the `Timeout` type is
+/// `()`. A closure which checks a free-running timer against a deadline you
+/// update between calls works well.
+pub struct SpinBlockingTxWithTimeout<T, F> {
+    inner: T,
+    deadline_exceeded: F,
+}
+
+impl<T, F> SpinBlockingTxWithTimeout<T, F>
+    where T: MutNonBlockingTx,
+          F: FnMut() -> bool
+{
+    /// Wrap a transmitter, with a predicate which returns true once the
+    /// deadline has passed.
+    pub fn new(inner: T, deadline_exceeded: F) -> SpinBlockingTxWithTimeout<T, F> {
+        SpinBlockingTxWithTimeout {
+            inner,
+            deadline_exceeded,
+        }
+    }
+
+    /// Release the wrapped transmitter and the predicate.
+    pub fn into_inner(self) -> (T, F) {
+        (self.inner, self.deadline_exceeded)
+    }
+}
+
+impl<T, F> MutBlockingTxWithTimeout for SpinBlockingTxWithTimeout<T, F>
+    where T: MutNonBlockingTx,
+          F: FnMut() -> bool
+{
+    type Timeout = ();
+    type Error = T::Error;
+
+    fn putc_wait(&mut self, ch: u8, _timeout: &()) -> Result<Option<u8>, Self::Error> {
+        loop {
+            if let Some(ch) = self.inner.putc_try(ch)? {
+                return Ok(Some(ch));
+            }
+            if (self.deadline_exceeded)() {
+                return Ok(None);
+            }
+            spin_loop();
+        }
+    }
+}
+
+/// Wraps a `MutNonBlockingRx` and implements `MutBlockingRxWithTimeout` by
+/// calling `getc_try` until an octet arrives or `deadline_exceeded` returns
+/// true.
+///
+/// As with `SpinBlockingTxWithTimeout`, the `Timeout` type is `()`.
+pub struct SpinBlockingRxWithTimeout<T, F> {
+    inner: T,
+    deadline_exceeded: F,
+}
+
+impl<T, F> SpinBlockingRxWithTimeout<T, F>
+    where T: MutNonBlockingRx,
+          F: FnMut() -> bool
+{
+    /// Wrap a receiver, with a predicate which returns true once the
+    /// deadline has passed.
+    pub fn new(inner: T, deadline_exceeded: F) -> SpinBlockingRxWithTimeout<T, F> {
+        SpinBlockingRxWithTimeout {
+            inner,
+            deadline_exceeded,
+        }
+    }
+
+    /// Release the wrapped receiver and the predicate.
+    pub fn into_inner(self) -> (T, F) {
+        (self.inner, self.deadline_exceeded)
+    }
+}
+
+impl<T, F> MutBlockingRxWithTimeout for SpinBlockingRxWithTimeout<T, F>
+    where T: MutNonBlockingRx,
+          F: FnMut() -> bool
+{
+    type Timeout = ();
+    type Error = T::Error;
+
+    fn getc_wait(&mut self, _timeout: &()) -> Result<Option<u8>, Self::Error> {
+        loop {
+            if let Some(ch) = self.inner.getc_try()? {
+                return Ok(Some(ch));
+            }
+            if (self.deadline_exceeded)() {
+                return Ok(None);
+            }
+            spin_loop();
+        }
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,680 @@
+//! Counting the traffic through a port.
+
+#[cfg(not(feature = "atomic"))]
+use core::cell::Cell;
+#[cfg(feature = "atomic")]
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::{ImmutBlockingRx, ImmutBlockingRxWithTimeout, ImmutBlockingTx,
+            ImmutBlockingTxWithTimeout, ImmutNonBlockingRx, ImmutNonBlockingRxWithTimeout,
+            ImmutNonBlockingTx, ImmutNonBlockingTxWithTimeout, MutBlockingRx,
+            MutBlockingRxWithTimeout, MutBlockingTx, MutBlockingTxWithTimeout, MutNonBlockingRx,
+            MutNonBlockingRxWithTimeout, MutNonBlockingTx, RxReady};
+
+/// A snapshot of the counters kept by a `StatisticsCollector`. All the
+/// counters wrap around on overflow.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SerialStats {
+    /// The number of octets sent.
+    pub tx_bytes: u32,
+    /// The number of octets received.
+    pub rx_bytes: u32,
+    /// The number of send calls which returned an error.
+    pub tx_errors: u32,
+    /// The number of receive calls which returned an error.
+    pub rx_errors: u32,
+}
+
+/// A counter which can be updated through a shared reference.
+#[cfg(not(feature = "atomic"))]
+struct Counter(Cell<u32>);
+
+/// A counter which can be updated through a shared reference.
+#[cfg(feature = "atomic")]
+struct Counter(AtomicU32);
+
+#[cfg(not(feature = "atomic"))]
+impl Counter {
+    fn new() -> Counter {
+        Counter(Cell::new(0))
+    }
+
+    fn add(&self, count: u32) {
+        self.0.set(self.0.get().wrapping_add(count));
+    }
+
+    fn get(&self) -> u32 {
+        self.0.get()
+    }
+
+    fn reset(&self) {
+        self.0.set(0);
+    }
+}
+
+#[cfg(feature = "atomic")]
+impl Counter {
+    fn new() -> Counter {
+        Counter(AtomicU32::new(0))
+    }
+
+    fn add(&self, count: u32) {
+        self.0.fetch_add(count, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u32 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn reset(&self) {
+        self.0.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Wraps a port and counts the octets sent and received through it, and the
+/// calls which failed, for health monitoring.
+///
+/// Every trait that the wrapped port implements is implemented. Only the
+/// calls which move data are counted - not `flush`, `bytes_available` and
+/// the like - and a timeout isn't counted as an error.
+///
+/// The counters are kept in `Cell`s, so they can be updated through the
+/// `Immut*` traits. With the `atomic` feature they are `AtomicU32`s
+/// instead, which makes the collector `Sync` so it can be shared with an
+/// interrupt handler, but needs a target with atomic read-modify-write
+/// instructions.
+pub struct StatisticsCollector<T> {
+    inner: T,
+    tx_bytes: Counter,
+    rx_bytes: Counter,
+    tx_errors: Counter,
+    rx_errors: Counter,
+}
+
+impl<T> StatisticsCollector<T> {
+    /// Wrap a port, with all the counters at zero.
+    pub fn new(inner: T) -> StatisticsCollector<T> {
+        StatisticsCollector {
+            inner,
+            tx_bytes: Counter::new(),
+            rx_bytes: Counter::new(),
+            tx_errors: Counter::new(),
+            rx_errors: Counter::new(),
+        }
+    }
+
+    /// Read the counters.
+    pub fn stats(&self) -> SerialStats {
+        SerialStats {
+            tx_bytes: self.tx_bytes.get(),
+            rx_bytes: self.rx_bytes.get(),
+            tx_errors: self.tx_errors.get(),
+            rx_errors: self.rx_errors.get(),
+        }
+    }
+
+    /// Set all the counters back to zero.
+    pub fn reset_stats(&self) {
+        self.tx_bytes.reset();
+        self.rx_bytes.reset();
+        self.tx_errors.reset();
+        self.rx_errors.reset();
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+/// Count `octets` moved, and an error if the call `failed`.
+fn tally(bytes: &Counter, errors: &Counter, octets: usize, failed: bool) {
+    // Truncation is fine, as the counters wrap anyway.
+    bytes.add(octets as u32);
+    if failed {
+        errors.add(1);
+    }
+}
+
+impl<T> MutBlockingTx for StatisticsCollector<T>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        let result = self.inner.putc(ch);
+        tally(&self.tx_bytes, &self.tx_errors, usize::from(result.is_ok()), result.is_err());
+        result
+    }
+
+    fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let data = data.as_ref();
+        let result = self.inner.puts(data);
+        let moved = match result {
+            Ok(()) => data.len(),
+            Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+
+    fn puts_repeat(&mut self, byte: u8, count: usize) -> Result<(), (usize, Self::Error)> {
+        let result = self.inner.puts_repeat(byte, count);
+        let moved = match result {
+            Ok(()) => count,
+            Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+}
+
+impl<T> MutBlockingTxWithTimeout for StatisticsCollector<T>
+    where T: MutBlockingTxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn putc_wait(&mut self, ch: u8, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.putc_wait(ch, timeout);
+        let moved = matches!(result, Ok(Some(_)));
+        tally(&self.tx_bytes, &self.tx_errors, usize::from(moved), result.is_err());
+        result
+    }
+
+    fn puts_wait<I>(&mut self,
+                    data: &I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let result = self.inner.puts_wait(data, timeout);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+
+    fn flush_wait(&mut self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        self.inner.flush_wait(timeout)
+    }
+
+    fn puts_repeat_wait(&mut self,
+                        byte: u8,
+                        count: usize,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)> {
+        let result = self.inner.puts_repeat_wait(byte, count, timeout);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+}
+
+impl<T> MutNonBlockingTx for StatisticsCollector<T>
+    where T: MutNonBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc_try(&mut self, ch: u8) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.putc_try(ch);
+        let moved = matches!(result, Ok(Some(_)));
+        tally(&self.tx_bytes, &self.tx_errors, usize::from(moved), result.is_err());
+        result
+    }
+
+    fn puts_try<I>(&mut self, data: &I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let result = self.inner.puts_try(data);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+
+    fn puts_repeat_try(&mut self, byte: u8, count: usize) -> Result<usize, (usize, Self::Error)> {
+        let result = self.inner.puts_repeat_try(byte, count);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+}
+
+impl<T> MutBlockingRx for StatisticsCollector<T>
+    where T: MutBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        let result = self.inner.getc();
+        tally(&self.rx_bytes, &self.rx_errors, usize::from(result.is_ok()), result.is_err());
+        result
+    }
+
+    fn gets<I>(&mut self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        let buffer = buffer.as_mut();
+        let result = self.inner.gets(buffer);
+        let moved = match result {
+            Ok(()) => buffer.len(),
+            Err((moved, _)) => moved,
+        };
+        tally(&self.rx_bytes, &self.rx_errors, moved, result.is_err());
+        result
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+impl<T> MutBlockingRxWithTimeout for StatisticsCollector<T>
+    where T: MutBlockingRxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn getc_wait(&mut self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.getc_wait(timeout);
+        let moved = matches!(result, Ok(Some(_)));
+        tally(&self.rx_bytes, &self.rx_errors, usize::from(moved), result.is_err());
+        result
+    }
+
+    fn gets_wait<I>(&mut self,
+                    buffer: &mut I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        let result = self.inner.gets_wait(buffer, timeout);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.rx_bytes, &self.rx_errors, moved, result.is_err());
+        result
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+impl<T> RxReady for StatisticsCollector<T>
+    where T: RxReady
+{
+    fn wait_for_rx_ready(&mut self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        self.inner.wait_for_rx_ready(timeout)
+    }
+}
+
+impl<T> MutNonBlockingRx for StatisticsCollector<T>
+    where T: MutNonBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc_try(&mut self) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.getc_try();
+        let moved = matches!(result, Ok(Some(_)));
+        tally(&self.rx_bytes, &self.rx_errors, usize::from(moved), result.is_err());
+        result
+    }
+
+    fn gets_try<I>(&mut self, buffer: &mut I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        let result = self.inner.gets_try(buffer);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.rx_bytes, &self.rx_errors, moved, result.is_err());
+        result
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+impl<T> MutNonBlockingRxWithTimeout for StatisticsCollector<T>
+    where T: MutNonBlockingRxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn getc_try_wait(&mut self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.getc_try_wait(timeout);
+        let moved = matches!(result, Ok(Some(_)));
+        tally(&self.rx_bytes, &self.rx_errors, usize::from(moved), result.is_err());
+        result
+    }
+
+    fn gets_try_wait<I>(&mut self,
+                        buffer: &mut I,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        let result = self.inner.gets_try_wait(buffer, timeout);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.rx_bytes, &self.rx_errors, moved, result.is_err());
+        result
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+impl<T> ImmutBlockingTx for StatisticsCollector<T>
+    where T: ImmutBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&self, ch: u8) -> Result<(), Self::Error> {
+        let result = self.inner.putc(ch);
+        tally(&self.tx_bytes, &self.tx_errors, usize::from(result.is_ok()), result.is_err());
+        result
+    }
+
+    fn puts<I>(&self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let data = data.as_ref();
+        let result = self.inner.puts(data);
+        let moved = match result {
+            Ok(()) => data.len(),
+            Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+
+    fn puts_repeat(&self, byte: u8, count: usize) -> Result<(), (usize, Self::Error)> {
+        let result = self.inner.puts_repeat(byte, count);
+        let moved = match result {
+            Ok(()) => count,
+            Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+}
+
+impl<T> ImmutBlockingTxWithTimeout for StatisticsCollector<T>
+    where T: ImmutBlockingTxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn putc_wait(&self, ch: u8, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.putc_wait(ch, timeout);
+        let moved = matches!(result, Ok(Some(_)));
+        tally(&self.tx_bytes, &self.tx_errors, usize::from(moved), result.is_err());
+        result
+    }
+
+    fn puts_wait<I>(&self,
+                    data: &I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let result = self.inner.puts_wait(data, timeout);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+
+    fn flush_wait(&self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        self.inner.flush_wait(timeout)
+    }
+
+    fn puts_repeat_wait(&self,
+                        byte: u8,
+                        count: usize,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)> {
+        let result = self.inner.puts_repeat_wait(byte, count, timeout);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+}
+
+impl<T> ImmutNonBlockingTx for StatisticsCollector<T>
+    where T: ImmutNonBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc_try(&self, ch: u8) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.putc_try(ch);
+        let moved = matches!(result, Ok(Some(_)));
+        tally(&self.tx_bytes, &self.tx_errors, usize::from(moved), result.is_err());
+        result
+    }
+
+    fn puts_try<I>(&self, data: &I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let result = self.inner.puts_try(data);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+
+    fn puts_repeat_try(&self, byte: u8, count: usize) -> Result<usize, (usize, Self::Error)> {
+        let result = self.inner.puts_repeat_try(byte, count);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+}
+
+impl<T> ImmutNonBlockingTxWithTimeout for StatisticsCollector<T>
+    where T: ImmutNonBlockingTxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn putc_try_wait(&self, ch: u8, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.putc_try_wait(ch, timeout);
+        let moved = matches!(result, Ok(Some(_)));
+        tally(&self.tx_bytes, &self.tx_errors, usize::from(moved), result.is_err());
+        result
+    }
+
+    fn puts_try_wait<I>(&self,
+                        data: &I,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let result = self.inner.puts_try_wait(data, timeout);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+
+    fn flush_wait(&self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        self.inner.flush_wait(timeout)
+    }
+
+    fn puts_repeat_try_wait(&self,
+                            byte: u8,
+                            count: usize,
+                            timeout: &Self::Timeout)
+                            -> Result<usize, (usize, Self::Error)> {
+        let result = self.inner.puts_repeat_try_wait(byte, count, timeout);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.tx_bytes, &self.tx_errors, moved, result.is_err());
+        result
+    }
+}
+
+impl<T> ImmutBlockingRx for StatisticsCollector<T>
+    where T: ImmutBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc(&self) -> Result<u8, Self::Error> {
+        let result = self.inner.getc();
+        tally(&self.rx_bytes, &self.rx_errors, usize::from(result.is_ok()), result.is_err());
+        result
+    }
+
+    fn gets<I>(&self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        let buffer = buffer.as_mut();
+        let result = self.inner.gets(buffer);
+        let moved = match result {
+            Ok(()) => buffer.len(),
+            Err((moved, _)) => moved,
+        };
+        tally(&self.rx_bytes, &self.rx_errors, moved, result.is_err());
+        result
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+impl<T> ImmutBlockingRxWithTimeout for StatisticsCollector<T>
+    where T: ImmutBlockingRxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn getc_wait(&self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.getc_wait(timeout);
+        let moved = matches!(result, Ok(Some(_)));
+        tally(&self.rx_bytes, &self.rx_errors, usize::from(moved), result.is_err());
+        result
+    }
+
+    fn gets_wait<I>(&self,
+                    buffer: &mut I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        let result = self.inner.gets_wait(buffer, timeout);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.rx_bytes, &self.rx_errors, moved, result.is_err());
+        result
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+impl<T> ImmutNonBlockingRx for StatisticsCollector<T>
+    where T: ImmutNonBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc_try(&self) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.getc_try();
+        let moved = matches!(result, Ok(Some(_)));
+        tally(&self.rx_bytes, &self.rx_errors, usize::from(moved), result.is_err());
+        result
+    }
+
+    fn gets_try<I>(&self, buffer: &mut I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        let result = self.inner.gets_try(buffer);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.rx_bytes, &self.rx_errors, moved, result.is_err());
+        result
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+impl<T> ImmutNonBlockingRxWithTimeout for StatisticsCollector<T>
+    where T: ImmutNonBlockingRxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn getc_try_wait(&self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        let result = self.inner.getc_try_wait(timeout);
+        let moved = matches!(result, Ok(Some(_)));
+        tally(&self.rx_bytes, &self.rx_errors, usize::from(moved), result.is_err());
+        result
+    }
+
+    fn gets_try_wait<I>(&self,
+                        buffer: &mut I,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        let result = self.inner.gets_try_wait(buffer, timeout);
+        let moved = match result {
+            Ok(moved) | Err((moved, _)) => moved,
+        };
+        tally(&self.rx_bytes, &self.rx_errors, moved, result.is_err());
+        result
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,102 @@
+//! Sending the same data to two ports.
+
+use crate::{ImmutBlockingTx, MutBlockingTx};
+
+/// The error returned by `Tee` and `ImmutTee` - whichever of the two ports
+/// failed first.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TeeError<EA, EB> {
+    /// The first port returned an error.
+    A(EA),
+    /// The second port returned an error.
+    B(EB),
+}
+
+/// Wraps two transmitters and sends every octet to both, e.g. a UART and a
+/// debug log.
+///
+/// Each octet goes to `A` first. If `A` fails, `B` is still given the octet,
+/// and the error from `A` is returned.
+pub struct Tee<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> Tee<A, B>
+    where A: MutBlockingTx,
+          B: MutBlockingTx
+{
+    /// Wrap two transmitters.
+    pub fn new(a: A, b: B) -> Tee<A, B> {
+        Tee { a, b }
+    }
+
+    /// Release the wrapped transmitters.
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+impl<A, B> MutBlockingTx for Tee<A, B>
+    where A: MutBlockingTx,
+          B: MutBlockingTx
+{
+    type Error = TeeError<A::Error, B::Error>;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        first_error(self.a.putc(ch), self.b.putc(ch))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        first_error(self.a.flush(), self.b.flush())
+    }
+}
+
+/// As `Tee`, but for transmitters which implement `ImmutBlockingTx`.
+pub struct ImmutTee<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> ImmutTee<A, B>
+    where A: ImmutBlockingTx,
+          B: ImmutBlockingTx
+{
+    /// Wrap two transmitters.
+    pub fn new(a: A, b: B) -> ImmutTee<A, B> {
+        ImmutTee { a, b }
+    }
+
+    /// Release the wrapped transmitters.
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+impl<A, B> ImmutBlockingTx for ImmutTee<A, B>
+    where A: ImmutBlockingTx,
+          B: ImmutBlockingTx
+{
+    type Error = TeeError<A::Error, B::Error>;
+
+    fn putc(&self, ch: u8) -> Result<(), Self::Error> {
+        first_error(self.a.putc(ch), self.b.putc(ch))
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        first_error(self.a.flush(), self.b.flush())
+    }
+}
+
+/// Combine the results of doing the same thing to both ports, preferring
+/// the error from `A`.
+fn first_error<EA, EB>(a: Result<(), EA>, b: Result<(), EB>) -> Result<(), TeeError<EA, EB>> {
+    a.map_err(TeeError::A)?;
+    b.map_err(TeeError::B)
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
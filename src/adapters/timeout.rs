@@ -0,0 +1,64 @@
+//! Timeouts for non-blocking receivers, timed with a delay.
+
+use crate::delay::DelayUs;
+use crate::{MutBlockingRxWithTimeout, MutNonBlockingRx};
+
+/// Wraps a `MutNonBlockingRx` and a `DelayUs`, and implements
+/// `MutBlockingRxWithTimeout` with the timeout given in microseconds.
+///
+/// `getc_try` is called repeatedly, with a 1 µs delay between attempts,
+/// until an octet arrives or the timeout's worth of delays have been made.
+/// The time taken by `getc_try` itself isn't counted, so the real timeout
+/// is somewhat longer than asked for.
+pub struct TimeoutAdapter<T, D> {
+    inner: T,
+    delay: D,
+}
+
+impl<T, D> TimeoutAdapter<T, D>
+    where T: MutNonBlockingRx,
+          D: DelayUs
+{
+    /// Wrap a receiver, using `delay` to time the timeouts.
+    pub fn new(inner: T, delay: D) -> TimeoutAdapter<T, D> {
+        TimeoutAdapter { inner, delay }
+    }
+
+    /// Release the wrapped receiver and the delay.
+    pub fn into_inner(self) -> (T, D) {
+        (self.inner, self.delay)
+    }
+}
+
+impl<T, D> MutBlockingRxWithTimeout for TimeoutAdapter<T, D>
+    where T: MutNonBlockingRx,
+          D: DelayUs
+{
+    /// The timeout, in microseconds.
+    type Timeout = u32;
+    type Error = T::Error;
+
+    fn getc_wait(&mut self, timeout: &u32) -> Result<Option<u8>, Self::Error> {
+        let mut elapsed: u32 = 0;
+        loop {
+            if let Some(ch) = self.inner.getc_try()? {
+                return Ok(Some(ch));
+            }
+            if elapsed >= *timeout {
+                return Ok(None);
+            }
+            self.delay.delay_us(1);
+            elapsed += 1;
+        }
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
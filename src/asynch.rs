@@ -0,0 +1,224 @@
+//! # Async traits
+//!
+//! Async versions of the blocking traits, for use with async executors such
+//! as Embassy or RTIC v2. Each method has the same signature as its blocking
+//! counterpart, but is an `async fn`. There are no timeout or non-blocking
+//! variants: an async call doesn't tie up the CPU while it waits, and a
+//! timeout can be had by racing the call against a timer.
+//!
+//! Enable the `async` feature to use this module. It needs Rust 1.75 or
+//! later.
+//!
+//! `FromSync` lets a port which only implements the blocking traits be
+//! used where the async traits are needed.
+
+// Embedded executors generally run on a single thread, so there's no need
+// to promise the futures are `Send`.
+#![allow(async_fn_in_trait)]
+
+use crate::{ImmutBlockingRx, ImmutBlockingTx, MutBlockingRx, MutBlockingTx};
+
+/// Implementors of this trait offer octet based serial data transmission
+/// using an async API and requiring a mutable reference to self.
+pub trait AsyncMutBlockingTx {
+    /// The error type returned if a function fails.
+    type Error;
+
+    /// Write a single octet to the port's transmitter, waiting until the
+    /// octet can be stored in the buffer (not necessarily that the octet has
+    /// been transmitted).
+    async fn putc(&mut self, ch: u8) -> Result<(), Self::Error>;
+
+    /// Write a complete string to the UART.
+    /// If this returns `Ok(())`, all the data was sent.
+    /// Otherwise you get number of octets sent and the error.
+    async fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        for (count, octet) in data.as_ref().iter().enumerate() {
+            self.putc(*octet).await.map_err(|e| (count, e))?;
+        }
+        Ok(())
+    }
+
+    /// Wait until every octet accepted so far has actually been shifted out
+    /// onto the wire. The default implementation does nothing.
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Implementors of this trait offer octet based serial data reception using
+/// an async API and requiring a mutable reference to self.
+pub trait AsyncMutBlockingRx {
+    /// The error type returned if a function fails.
+    type Error;
+
+    /// Read a single octet from the port's receiver, waiting until one
+    /// arrives.
+    async fn getc(&mut self) -> Result<u8, Self::Error>;
+
+    /// Read a specified number of octets into the given buffer, waiting
+    /// until that many have been read.
+    async fn gets<I>(&mut self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        for (count, space) in buffer.as_mut().iter_mut().enumerate() {
+            *space = self.getc().await.map_err(|e| (count, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Implementors of this trait offer octet based serial data transmission
+/// using an async API and requiring just an immutable reference to self.
+pub trait AsyncImmutBlockingTx {
+    /// The error type returned if a function fails.
+    type Error;
+
+    /// Write a single octet to the port's transmitter, waiting until the
+    /// octet can be stored in the buffer (not necessarily that the octet has
+    /// been transmitted).
+    async fn putc(&self, ch: u8) -> Result<(), Self::Error>;
+
+    /// Write a complete string to the UART.
+    /// If this returns `Ok(())`, all the data was sent.
+    /// Otherwise you get number of octets sent and the error.
+    async fn puts<I>(&self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        for (count, octet) in data.as_ref().iter().enumerate() {
+            self.putc(*octet).await.map_err(|e| (count, e))?;
+        }
+        Ok(())
+    }
+
+    /// Wait until every octet accepted so far has actually been shifted out
+    /// onto the wire. The default implementation does nothing.
+    async fn flush(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Implementors of this trait offer octet based serial data reception using
+/// an async API and requiring just an immutable reference to self.
+pub trait AsyncImmutBlockingRx {
+    /// The error type returned if a function fails.
+    type Error;
+
+    /// Read a single octet from the port's receiver, waiting until one
+    /// arrives.
+    async fn getc(&self) -> Result<u8, Self::Error>;
+
+    /// Read a specified number of octets into the given buffer, waiting
+    /// until that many have been read.
+    async fn gets<I>(&self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        for (count, space) in buffer.as_mut().iter_mut().enumerate() {
+            *space = self.getc().await.map_err(|e| (count, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Wraps a port which implements the blocking traits, and implements the
+/// matching async traits. Each call does the blocking operation and then
+/// completes straight away, so it holds up the executor for as long as the
+/// operation blocks.
+///
+/// This is a wrapper rather than a blanket impl so that a port can have
+/// both a blocking and a real async implementation.
+pub struct FromSync<T> {
+    inner: T,
+}
+
+impl<T> FromSync<T> {
+    /// Wrap a port.
+    pub fn new(inner: T) -> FromSync<T> {
+        FromSync { inner }
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> AsyncMutBlockingTx for FromSync<T>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    async fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.inner.putc(ch)
+    }
+
+    async fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.inner.puts(data)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+impl<T> AsyncMutBlockingRx for FromSync<T>
+    where T: MutBlockingRx
+{
+    type Error = T::Error;
+
+    async fn getc(&mut self) -> Result<u8, Self::Error> {
+        self.inner.getc()
+    }
+
+    async fn gets<I>(&mut self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.inner.gets(buffer)
+    }
+}
+
+impl<T> AsyncImmutBlockingTx for FromSync<T>
+    where T: ImmutBlockingTx
+{
+    type Error = T::Error;
+
+    async fn putc(&self, ch: u8) -> Result<(), Self::Error> {
+        self.inner.putc(ch)
+    }
+
+    async fn puts<I>(&self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        self.inner.puts(data)
+    }
+
+    async fn flush(&self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+impl<T> AsyncImmutBlockingRx for FromSync<T>
+    where T: ImmutBlockingRx
+{
+    type Error = T::Error;
+
+    async fn getc(&self) -> Result<u8, Self::Error> {
+        self.inner.getc()
+    }
+
+    async fn gets<I>(&self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        self.inner.gets(buffer)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
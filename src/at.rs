@@ -0,0 +1,204 @@
+//! # AT commands
+//!
+//! A helper for talking to modems (and the many other devices which copied
+//! them) with AT commands: send a command, collect the lines which come back
+//! until a final result code, and keep any unsolicited result codes (URCs)
+//! which turn up in the middle for later.
+
+use crate::{MutBlockingRxWithTimeout, MutBlockingTx};
+
+/// The longest line an `AtResponse` keeps, unless told otherwise. The rest
+/// of a longer line is read but thrown away.
+pub const AT_LINE_LEN: usize = 64;
+
+/// Up to `N` lines of text, each of up to `L` octets, received from an AT
+/// device (or from `SerialRxWithTimeoutExt::read_response_lines`), without
+/// their line endings.
+pub struct AtResponse<const N: usize, const L: usize = AT_LINE_LEN> {
+    lines: [[u8; L]; N],
+    lens: [usize; N],
+    count: usize,
+}
+
+impl<const N: usize, const L: usize> AtResponse<N, L> {
+    pub(crate) fn new() -> AtResponse<N, L> {
+        AtResponse {
+            lines: [[0; L]; N],
+            lens: [0; N],
+            count: 0,
+        }
+    }
+
+    /// Store a line of up to `L` octets. Returns false if there was no room.
+    pub(crate) fn push(&mut self, line: &[u8]) -> bool {
+        if self.count == N {
+            return false;
+        }
+        self.lines[self.count][..line.len()].copy_from_slice(line);
+        self.lens[self.count] = line.len();
+        self.count += 1;
+        true
+    }
+
+    /// The number of lines.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns true if there are no lines.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Get a line, or `None` if `idx` is out of range.
+    pub fn get(&self, idx: usize) -> Option<&[u8]> {
+        if idx < self.count {
+            Some(&self.lines[idx][..self.lens[idx]])
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over the lines, in the order they arrived.
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        (0..self.count).map(move |idx| &self.lines[idx][..self.lens[idx]])
+    }
+}
+
+/// The ways in which `AtCommandPort::send_command` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AtError<E> {
+    /// The device replied `ERROR`.
+    Error,
+    /// The device replied `+CME ERROR`, with the given error code if it was
+    /// numeric.
+    CmeError(Option<u16>),
+    /// No final result code arrived before the timeout.
+    Timeout,
+    /// The response had more than `N` lines. The extra lines were thrown
+    /// away, but the final result code was still read.
+    TooManyLines,
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Wraps a port connected to an AT device.
+///
+/// Lines starting with `+` which don't belong to the command being run
+/// (e.g. `+CREG: 1` in the middle of `AT+CSQ`) are taken to be URCs and
+/// kept, up to `N` of them, until `drain_urcs` is called. URCs which don't
+/// start with `+`, such as `RING`, can't be told apart from response lines.
+pub struct AtCommandPort<T, const N: usize> {
+    inner: T,
+    urcs: AtResponse<N>,
+}
+
+impl<T, const N: usize> AtCommandPort<T, N>
+    where T: MutBlockingTx + MutBlockingRxWithTimeout<Error = <T as MutBlockingTx>::Error>
+{
+    /// Wrap a port.
+    pub fn new(inner: T) -> AtCommandPort<T, N> {
+        AtCommandPort {
+            inner,
+            urcs: AtResponse::new(),
+        }
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Send `cmd` (e.g. `b"AT+CSQ"`) followed by `\r\n`, then read lines
+    /// until `OK`, `ERROR` or `+CME ERROR` arrives. Returns the lines before
+    /// the `OK`, leaving out blank lines, the echo of the command and any
+    /// URCs. The timeout applies to each octet individually.
+    // `Option::is_some_and` needs a newer compiler than we ask for.
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn send_command(&mut self,
+                        cmd: &[u8],
+                        timeout: &<T as MutBlockingRxWithTimeout>::Timeout)
+                        -> Result<AtResponse<N>, AtError<<T as MutBlockingTx>::Error>> {
+        self.inner.puts(cmd).map_err(|(_, e)| AtError::Inner(e))?;
+        self.inner.puts(b"\r\n").map_err(|(_, e)| AtError::Inner(e))?;
+        let prefix = response_prefix(cmd);
+        let mut response = AtResponse::new();
+        let mut overflow = false;
+        let mut buf = [0u8; AT_LINE_LEN];
+        loop {
+            let line = self.read_line(&mut buf, timeout)?;
+            if line.is_empty() || line == cmd {
+                continue;
+            }
+            if line == b"OK" {
+                break;
+            }
+            if line == b"ERROR" {
+                return Err(AtError::Error);
+            }
+            if let Some(code) = line.strip_prefix(b"+CME ERROR:") {
+                return Err(AtError::CmeError(parse_code(code)));
+            }
+            if line[0] == b'+' && !prefix.map_or(false, |p| line.starts_with(p)) {
+                // There's nowhere to put a URC if the buffer is full.
+                let _ = self.urcs.push(line);
+            } else if !response.push(line) {
+                overflow = true;
+            }
+        }
+        if overflow {
+            Err(AtError::TooManyLines)
+        } else {
+            Ok(response)
+        }
+    }
+
+    /// Take the URCs received so far, oldest first.
+    pub fn drain_urcs(&mut self) -> AtResponse<N> {
+        core::mem::replace(&mut self.urcs, AtResponse::new())
+    }
+
+    /// Read one line, without its line ending, keeping at most
+    /// `AT_LINE_LEN` octets of it.
+    fn read_line<'b>(&mut self,
+                     buf: &'b mut [u8; AT_LINE_LEN],
+                     timeout: &<T as MutBlockingRxWithTimeout>::Timeout)
+                     -> Result<&'b [u8], AtError<<T as MutBlockingTx>::Error>> {
+        let mut len = 0;
+        loop {
+            let ch = match self.inner.getc_wait(timeout).map_err(AtError::Inner)? {
+                Some(ch) => ch,
+                None => return Err(AtError::Timeout),
+            };
+            match ch {
+                b'\n' => break,
+                b'\r' => {}
+                _ if len < buf.len() => {
+                    buf[len] = ch;
+                    len += 1;
+                }
+                _ => {}
+            }
+        }
+        Ok(&buf[..len])
+    }
+}
+
+/// The prefix of the lines which answer `cmd` - `+CSQ` for `AT+CSQ`, or
+/// `+CGDCONT` for `AT+CGDCONT?` - if it is an extended command.
+fn response_prefix(cmd: &[u8]) -> Option<&[u8]> {
+    let name = cmd.get(2..).filter(|name| name.first() == Some(&b'+'))?;
+    let end = name.iter().position(|ch| *ch == b'=' || *ch == b'?').unwrap_or(name.len());
+    Some(&name[..end])
+}
+
+/// Parse the numeric code after `+CME ERROR:`.
+fn parse_code(text: &[u8]) -> Option<u16> {
+    core::str::from_utf8(text).ok()?.trim().parse().ok()
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
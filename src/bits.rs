@@ -0,0 +1,124 @@
+//! # Bit fields
+//!
+//! Some compact binary protocols (RTP headers, H.264 NAL unit headers, etc)
+//! pack fields which don't line up with octet boundaries. `BitReader` and
+//! `BitWriter` read and write such fields, most significant bit first.
+
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// Wraps a `MutBlockingRx` and reads arbitrary-width bit fields from it.
+///
+/// Bits are taken from each octet most significant bit first. Octets are
+/// only read from the port when more bits are needed.
+pub struct BitReader<T> {
+    inner: T,
+    cache: u8,
+    remaining: u8,
+}
+
+impl<T> BitReader<T>
+    where T: MutBlockingRx
+{
+    /// Wrap a receiver.
+    pub fn new(inner: T) -> BitReader<T> {
+        BitReader {
+            inner,
+            cache: 0,
+            remaining: 0,
+        }
+    }
+
+    /// Read a `count` bit field, returned in the low bits of the result.
+    ///
+    /// Panics if `count` is more than 32.
+    pub fn read_bits(&mut self, count: u8) -> Result<u32, T::Error> {
+        assert!(count <= 32, "can't read more than 32 bits at once");
+        let mut value: u32 = 0;
+        for _ in 0..count {
+            if self.remaining == 0 {
+                self.cache = self.inner.getc()?;
+                self.remaining = 8;
+            }
+            self.remaining -= 1;
+            let bit = (self.cache >> self.remaining) & 1;
+            value = (value << 1) | u32::from(bit);
+        }
+        Ok(value)
+    }
+
+    /// Discard any bits left over from the current octet, so the next read
+    /// starts on an octet boundary.
+    pub fn align(&mut self) {
+        self.remaining = 0;
+    }
+
+    /// Release the wrapped receiver. Any bits left over from the current
+    /// octet are lost.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+/// Wraps a `MutBlockingTx` and writes arbitrary-width bit fields to it.
+///
+/// Bits are packed into each octet most significant bit first. An octet is
+/// only sent once all eight of its bits have been written, or on `flush`.
+pub struct BitWriter<T> {
+    inner: T,
+    cache: u8,
+    used: u8,
+}
+
+impl<T> BitWriter<T>
+    where T: MutBlockingTx
+{
+    /// Wrap a transmitter.
+    pub fn new(inner: T) -> BitWriter<T> {
+        BitWriter {
+            inner,
+            cache: 0,
+            used: 0,
+        }
+    }
+
+    /// Write the low `count` bits of `val`.
+    ///
+    /// Panics if `count` is more than 32.
+    pub fn write_bits(&mut self, val: u32, count: u8) -> Result<(), T::Error> {
+        assert!(count <= 32, "can't write more than 32 bits at once");
+        for shift in (0..count).rev() {
+            let bit = ((val >> shift) & 1) as u8;
+            self.cache = (self.cache << 1) | bit;
+            self.used += 1;
+            if self.used == 8 {
+                self.inner.putc(self.cache)?;
+                self.cache = 0;
+                self.used = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Pad the current octet with zero bits and send it. Does nothing if
+    /// the bits written so far fill a whole number of octets.
+    pub fn flush(&mut self) -> Result<(), T::Error> {
+        if self.used != 0 {
+            self.inner.putc(self.cache << (8 - self.used))?;
+            self.cache = 0;
+            self.used = 0;
+        }
+        Ok(())
+    }
+
+    /// Release the wrapped transmitter. Any bits not yet sent are lost, so
+    /// call `flush` first.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
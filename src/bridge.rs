@@ -0,0 +1,326 @@
+//! # Bridges
+//!
+//! Types which forward data between two serial ports.
+
+use crate::error::NoError;
+use crate::{MutBlockingTx, MutNonBlockingRx, MutNonBlockingSerial, MutNonBlockingTx};
+
+/// Which part of a bridge failed, and the error from that port.
+///
+/// `EA` is port A's error type, `EB` is port B's and `EL` is the log's.
+/// Bridges without a log use `NoError` for it. Each bridged port must use
+/// the same error type for sending and receiving.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BridgeError<EA, EB, EL = NoError> {
+    /// Reading from port A failed.
+    RxA(EA),
+    /// Writing to port A failed.
+    TxA(EA),
+    /// Reading from port B failed.
+    RxB(EB),
+    /// Writing to port B failed.
+    TxB(EB),
+    /// Writing to the log failed. The octet was still forwarded.
+    Log(EL),
+}
+
+/// The error returned by `PortMirror::bridge_once`.
+pub type MirrorError<A, B, Log> = BridgeError<<A as MutBlockingTx>::Error,
+                                              <B as MutBlockingTx>::Error,
+                                              <Log as MutBlockingTx>::Error>;
+
+/// What `PortMirror::bridge_once` did.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BridgeEvent {
+    /// Neither port had any data waiting.
+    NoData,
+    /// An octet was forwarded from port A to port B.
+    ForwardedAtoB(u8),
+    /// An octet was forwarded from port B to port A.
+    ForwardedBtoA(u8),
+}
+
+/// Forwards data in both directions between two ports, copying everything
+/// to a third port as a log - like a man-in-the-middle bus monitor.
+///
+/// Each forwarded octet is logged as two octets: `>` (for A to B) or `<`
+/// (for B to A), then the octet itself.
+pub struct PortMirror<A, B, Log> {
+    a: A,
+    b: B,
+    log: Log,
+    a_first: bool,
+}
+
+impl<A, B, Log> PortMirror<A, B, Log>
+    where A: MutBlockingTx + MutNonBlockingRx<Error = <A as MutBlockingTx>::Error>,
+          B: MutBlockingTx + MutNonBlockingRx<Error = <B as MutBlockingTx>::Error>,
+          Log: MutBlockingTx
+{
+    /// Create a mirror between ports `a` and `b`, logging to `log`.
+    pub fn new(a: A, b: B, log: Log) -> PortMirror<A, B, Log> {
+        PortMirror {
+            a,
+            b,
+            log,
+            a_first: true,
+        }
+    }
+
+    /// Release the three ports.
+    pub fn into_inner(self) -> (A, B, Log) {
+        (self.a, self.b, self.log)
+    }
+
+    /// Check both ports (without blocking) and forward at most one octet.
+    /// The ports take turns at being checked first, so a busy port can't
+    /// starve the other one. Call this in a loop to run the bridge.
+    pub fn bridge_once(&mut self) -> Result<BridgeEvent, MirrorError<A, B, Log>> {
+        let a_first = self.a_first;
+        self.a_first = !a_first;
+        let event = if a_first {
+            match self.forward_a_to_b()? {
+                BridgeEvent::NoData => self.forward_b_to_a()?,
+                event => event,
+            }
+        } else {
+            match self.forward_b_to_a()? {
+                BridgeEvent::NoData => self.forward_a_to_b()?,
+                event => event,
+            }
+        };
+        Ok(event)
+    }
+
+    fn forward_a_to_b(&mut self) -> Result<BridgeEvent, MirrorError<A, B, Log>> {
+        match self.a.getc_try().map_err(BridgeError::RxA)? {
+            None => Ok(BridgeEvent::NoData),
+            Some(ch) => {
+                self.b.putc(ch).map_err(BridgeError::TxB)?;
+                self.log.puts(&[b'>', ch]).map_err(|(_, e)| BridgeError::Log(e))?;
+                Ok(BridgeEvent::ForwardedAtoB(ch))
+            }
+        }
+    }
+
+    fn forward_b_to_a(&mut self) -> Result<BridgeEvent, MirrorError<A, B, Log>> {
+        match self.b.getc_try().map_err(BridgeError::RxB)? {
+            None => Ok(BridgeEvent::NoData),
+            Some(ch) => {
+                self.a.putc(ch).map_err(BridgeError::TxA)?;
+                self.log.puts(&[b'<', ch]).map_err(|(_, e)| BridgeError::Log(e))?;
+                Ok(BridgeEvent::ForwardedBtoA(ch))
+            }
+        }
+    }
+}
+
+/// How many octets `TransparentBridge` moves in each direction per poll.
+pub const TRANSPARENT_CHUNK: usize = 32;
+
+/// Forwards all data in both directions between two ports, as quickly as
+/// possible.
+///
+/// Each poll reads whatever is waiting on each port (up to
+/// `TRANSPARENT_CHUNK` octets) with `gets_try` and sends it on to the other
+/// port with `puts`. If that `puts` fails, the octets it didn't send are
+/// lost.
+pub struct TransparentBridge<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> TransparentBridge<A, B>
+    where A: MutBlockingTx + MutNonBlockingRx<Error = <A as MutBlockingTx>::Error>,
+          B: MutBlockingTx + MutNonBlockingRx<Error = <B as MutBlockingTx>::Error>
+{
+    /// Create a bridge between ports `a` and `b`.
+    pub fn new(a: A, b: B) -> TransparentBridge<A, B> {
+        TransparentBridge { a, b }
+    }
+
+    /// Release the two ports.
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+
+    /// Forward whatever data is waiting on each port to the other one.
+    /// Returns the number of octets forwarded from A to B, and from B to A.
+    ///
+    /// Both directions are serviced on every poll, even if one of them
+    /// fails, and then the first error is returned. If a read fails, any
+    /// octets it did read are still forwarded. If a write fails, the octets
+    /// it didn't send are lost.
+    pub fn poll_once(&mut self)
+                     -> Result<(usize, usize),
+                               BridgeError<<A as MutBlockingTx>::Error,
+                                           <B as MutBlockingTx>::Error>> {
+        let a_to_b = forward(&mut self.a, &mut self.b, BridgeError::RxA, BridgeError::TxB);
+        let b_to_a = forward(&mut self.b, &mut self.a, BridgeError::RxB, BridgeError::TxA);
+        match (a_to_b, b_to_a) {
+            (Ok(a_to_b), Ok(b_to_a)) => Ok((a_to_b, b_to_a)),
+            (Err(e), _) | (_, Err(e)) => Err(e),
+        }
+    }
+
+    /// Run the bridge for ever. Errors from either port are ignored - the
+    /// bridge just carries on with the next poll.
+    pub fn run_forever(&mut self) -> ! {
+        loop {
+            let _ = self.poll_once();
+        }
+    }
+}
+
+/// Move up to `TRANSPARENT_CHUNK` waiting octets from `from` to `to`. If
+/// `to` fails, the octets it didn't take are dropped.
+fn forward<F, T, R>(from: &mut F,
+                    to: &mut T,
+                    rx_error: impl FnOnce(F::Error) -> R,
+                    tx_error: impl FnOnce(T::Error) -> R)
+                    -> Result<usize, R>
+    where F: MutNonBlockingRx,
+          T: MutBlockingTx
+{
+    let mut buffer = [0u8; TRANSPARENT_CHUNK];
+    let (count, read_error) = match from.gets_try(&mut buffer) {
+        Ok(count) => (count, None),
+        Err((count, e)) => (count, Some(e)),
+    };
+    to.puts(&buffer[..count]).map_err(|(_, e)| tx_error(e))?;
+    match read_error {
+        None => Ok(count),
+        Some(e) => Err(rx_error(e)),
+    }
+}
+
+/// What one call to `SerialBridge::pump` did.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BridgeStats<EA, EB> {
+    /// The number of octets delivered from port A to port B.
+    pub a_to_b: usize,
+    /// The number of octets delivered from port B to port A.
+    pub b_to_a: usize,
+    /// The first error seen, if any. The bridge carries on regardless, and
+    /// nothing which was read is lost.
+    pub error: Option<BridgeError<EA, EB>>,
+}
+
+/// Octets read from one port which the other port hasn't accepted yet.
+struct Pending<const N: usize> {
+    data: [u8; N],
+    start: usize,
+    end: usize,
+}
+
+impl<const N: usize> Pending<N> {
+    /// Offer the pending octets to `to`. Returns how many it took.
+    fn send<T, R>(&mut self,
+                  to: &mut T,
+                  error: &mut Option<R>,
+                  kind: impl FnOnce(T::Error) -> R)
+                  -> usize
+        where T: MutNonBlockingTx
+    {
+        let sent = match to.puts_try(&self.data[self.start..self.end]) {
+            Ok(sent) => sent,
+            Err((sent, e)) => {
+                error.get_or_insert_with(|| kind(e));
+                sent
+            }
+        };
+        self.start += sent;
+        if self.start == self.end {
+            self.start = 0;
+            self.end = 0;
+        }
+        sent
+    }
+
+    /// If nothing is pending, read whatever `from` has waiting.
+    fn fill<F, R>(&mut self,
+                  from: &mut F,
+                  error: &mut Option<R>,
+                  kind: impl FnOnce(F::Error) -> R)
+        where F: MutNonBlockingRx
+    {
+        if self.end != 0 {
+            return;
+        }
+        self.end = match from.gets_try(&mut self.data) {
+            Ok(count) => count,
+            Err((count, e)) => {
+                error.get_or_insert_with(|| kind(e));
+                count
+            }
+        };
+    }
+}
+
+/// Forwards data in both directions between two ports without ever
+/// blocking, e.g. between a UART and a USB CDC-ACM port.
+///
+/// Up to `N` octets read from each port are held until the other port
+/// accepts them, so a port which is slow to send doesn't lose data - it
+/// stops the bridge reading from the other side instead.
+pub struct SerialBridge<A, B, const N: usize> {
+    a: A,
+    b: B,
+    a_to_b: Pending<N>,
+    b_to_a: Pending<N>,
+}
+
+impl<A, B, const N: usize> SerialBridge<A, B, N>
+    where A: MutNonBlockingSerial,
+          B: MutNonBlockingSerial
+{
+    /// Create a bridge between ports `a` and `b`.
+    pub fn new(a: A, b: B) -> SerialBridge<A, B, N> {
+        SerialBridge {
+            a,
+            b,
+            a_to_b: Pending {
+                data: [0; N],
+                start: 0,
+                end: 0,
+            },
+            b_to_a: Pending {
+                data: [0; N],
+                start: 0,
+                end: 0,
+            },
+        }
+    }
+
+    /// Release the two ports. Any octets still waiting to be delivered are
+    /// lost.
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+
+    /// Move as much data as each port will give and take right now. In each
+    /// direction, anything still pending from an earlier call is delivered
+    /// first, then (once that has all gone) up to `N` more octets are read
+    /// and offered to the other port.
+    pub fn pump(&mut self)
+                -> BridgeStats<<A as MutNonBlockingTx>::Error, <B as MutNonBlockingTx>::Error> {
+        let mut error = None;
+        let mut a_to_b = self.a_to_b.send(&mut self.b, &mut error, BridgeError::TxB);
+        self.a_to_b.fill(&mut self.a, &mut error, BridgeError::RxA);
+        a_to_b += self.a_to_b.send(&mut self.b, &mut error, BridgeError::TxB);
+        let mut b_to_a = self.b_to_a.send(&mut self.a, &mut error, BridgeError::TxA);
+        self.b_to_a.fill(&mut self.b, &mut error, BridgeError::RxB);
+        b_to_a += self.b_to_a.send(&mut self.a, &mut error, BridgeError::TxA);
+        BridgeStats {
+            a_to_b,
+            b_to_a,
+            error,
+        }
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
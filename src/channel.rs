@@ -0,0 +1,110 @@
+//! # Multi-channel UARTs
+//!
+//! Some UARTs (USB CDC with multiple interfaces, Bluetooth RFCOMM, etc)
+//! carry several logical channels over a single physical port. The
+//! `MultiChannelUart` trait describes such a port, and hands out handles
+//! which implement the ordinary single-channel traits for one channel at a
+//! time.
+
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// Implementors of this trait offer octet based, blocking, serial data
+/// transmission and reception over `CHANNELS` logical channels, numbered
+/// from zero.
+pub trait MultiChannelUart<const CHANNELS: usize> {
+    /// The error type returned if a function fails.
+    type Error;
+
+    /// Write a single octet to the given channel's transmitter, blocking
+    /// until the octet can be stored in the buffer.
+    fn putc_channel(&mut self, ch: usize, octet: u8) -> Result<(), Self::Error>;
+
+    /// Read a single octet from the given channel's receiver, blocking until
+    /// an octet is available.
+    fn getc_channel(&mut self, ch: usize) -> Result<u8, Self::Error>;
+
+    /// Get a handle which transmits everything written to it on channel
+    /// `ch`.
+    ///
+    /// Panics if `ch` is not less than `CHANNELS`.
+    fn channel_tx(&mut self, ch: usize) -> ChannelTx<'_, Self, CHANNELS>
+        where Self: Sized
+    {
+        assert!(ch < CHANNELS, "channel {} out of range", ch);
+        ChannelTx { uart: self, ch }
+    }
+
+    /// Get a handle which receives everything read from it on channel `ch`.
+    ///
+    /// Panics if `ch` is not less than `CHANNELS`.
+    fn channel_rx(&mut self, ch: usize) -> ChannelRx<'_, Self, CHANNELS>
+        where Self: Sized
+    {
+        assert!(ch < CHANNELS, "channel {} out of range", ch);
+        ChannelRx { uart: self, ch }
+    }
+}
+
+/// A handle to the transmit side of one channel of a `MultiChannelUart`.
+///
+/// The UART is borrowed for as long as the handle exists.
+pub struct ChannelTx<'a, T, const CHANNELS: usize>
+    where T: 'a + MultiChannelUart<CHANNELS>
+{
+    uart: &'a mut T,
+    ch: usize,
+}
+
+impl<'a, T, const CHANNELS: usize> ChannelTx<'a, T, CHANNELS>
+    where T: MultiChannelUart<CHANNELS>
+{
+    /// The channel this handle writes to.
+    pub fn channel(&self) -> usize {
+        self.ch
+    }
+}
+
+impl<'a, T, const CHANNELS: usize> MutBlockingTx for ChannelTx<'a, T, CHANNELS>
+    where T: MultiChannelUart<CHANNELS>
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.uart.putc_channel(self.ch, ch)
+    }
+}
+
+/// A handle to the receive side of one channel of a `MultiChannelUart`.
+///
+/// The UART is borrowed for as long as the handle exists.
+pub struct ChannelRx<'a, T, const CHANNELS: usize>
+    where T: 'a + MultiChannelUart<CHANNELS>
+{
+    uart: &'a mut T,
+    ch: usize,
+}
+
+impl<'a, T, const CHANNELS: usize> ChannelRx<'a, T, CHANNELS>
+    where T: MultiChannelUart<CHANNELS>
+{
+    /// The channel this handle reads from.
+    pub fn channel(&self) -> usize {
+        self.ch
+    }
+}
+
+impl<'a, T, const CHANNELS: usize> MutBlockingRx for ChannelRx<'a, T, CHANNELS>
+    where T: MultiChannelUart<CHANNELS>
+{
+    type Error = T::Error;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        self.uart.getc_channel(self.ch)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
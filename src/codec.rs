@@ -0,0 +1,145 @@
+//! Encoding helpers shared between the traits and the adapters.
+
+use crate::ext::LineEnding;
+use crate::MutBlockingTx;
+
+/// The number of octets shown on each line of a hex dump.
+pub(crate) const HEX_DUMP_WIDTH: usize = 16;
+
+const HEX_DIGITS_LOWER: &[u8; 16] = b"0123456789abcdef";
+
+pub(crate) const HEX_DIGITS_UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+/// The most characters `format_decimal` can produce: ten digits and a sign.
+pub(crate) const DECIMAL_BUF_LEN: usize = 11;
+
+/// Format `val` as ASCII decimal, with a leading `-` if `negative` is set,
+/// into the end of `buf`. Returns the part of `buf` holding the text.
+pub(crate) fn format_decimal(val: u32, negative: bool, buf: &mut [u8; DECIMAL_BUF_LEN]) -> &[u8] {
+    let mut start = buf.len();
+    let mut val = val;
+    loop {
+        start -= 1;
+        buf[start] = b'0' + (val % 10) as u8;
+        val /= 10;
+        if val == 0 {
+            break;
+        }
+    }
+    if negative {
+        start -= 1;
+        buf[start] = b'-';
+    }
+    &buf[start..]
+}
+
+/// Convert an ASCII hex digit, in either case, to its value.
+pub(crate) fn hex_value(ch: u8) -> Option<u8> {
+    match ch {
+        b'0'..=b'9' => Some(ch - b'0'),
+        b'a'..=b'f' => Some(ch - b'a' + 10),
+        b'A'..=b'F' => Some(ch - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// The standard base64 alphabet, indexed by value.
+pub(crate) const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Convert a character from the standard base64 alphabet to its value.
+pub(crate) fn base64_value(ch: u8) -> Option<u8> {
+    match ch {
+        b'A'..=b'Z' => Some(ch - b'A'),
+        b'a'..=b'z' => Some(ch - b'a' + 26),
+        b'0'..=b'9' => Some(ch - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Write one line of a hex dump, `hexdump -C` style, for the (at most
+/// `HEX_DUMP_WIDTH`) octets in `chunk` found at `offset`.
+pub(crate) fn put_hex_dump_line<T>(tx: &mut T, offset: usize, chunk: &[u8]) -> Result<(), T::Error>
+    where T: ?Sized + MutBlockingTx
+{
+    for shift in (0..8).rev() {
+        tx.putc(HEX_DIGITS_LOWER[(offset >> (shift * 4)) & 0x0F])?;
+    }
+    tx.putc(b' ')?;
+    for idx in 0..HEX_DUMP_WIDTH {
+        tx.putc(b' ')?;
+        if idx == HEX_DUMP_WIDTH / 2 {
+            tx.putc(b' ')?;
+        }
+        match chunk.get(idx) {
+            Some(octet) => {
+                tx.putc(HEX_DIGITS_LOWER[usize::from(octet >> 4)])?;
+                tx.putc(HEX_DIGITS_LOWER[usize::from(octet & 0x0F)])?;
+            }
+            None => {
+                tx.putc(b' ')?;
+                tx.putc(b' ')?;
+            }
+        }
+    }
+    tx.putc(b' ')?;
+    tx.putc(b' ')?;
+    tx.putc(b'|')?;
+    for octet in chunk {
+        tx.putc(if octet.is_ascii_graphic() || *octet == b' ' {
+            *octet
+        } else {
+            b'.'
+        })?;
+    }
+    tx.putc(b'|')?;
+    tx.putc(b'\r')?;
+    tx.putc(b'\n')
+}
+
+/// Read a line into `buf`, getting each octet from `next`, which returns
+/// `Ok(None)` on a timeout. Returns the length of the line without its
+/// ending. If the line doesn't fit, the rest of it (and its ending) is read
+/// and thrown away, and `buf.len()` is returned.
+pub(crate) fn read_line<E, F>(buf: &mut [u8],
+                              ending: LineEnding,
+                              mut next: F)
+                              -> Result<usize, (usize, E)>
+    where F: FnMut() -> Result<Option<u8>, E>
+{
+    let mut count = 0;
+    let mut prev = None;
+    let mut dropped = false;
+    loop {
+        let ch = match next().map_err(|e| (count, e))? {
+            None => return Ok(count),
+            Some(ch) => ch,
+        };
+        let after_cr = prev == Some(b'\r');
+        match (ending, ch) {
+            (LineEnding::Lf, b'\n') | (LineEnding::Cr, b'\r') => return Ok(count),
+            (LineEnding::CrLf, b'\n') | (LineEnding::Any, b'\n') if after_cr => {
+                // The `\r` isn't part of the line, unless it was thrown away
+                return Ok(if dropped { count } else { count - 1 });
+            }
+            (LineEnding::Any, b'\n') => return Ok(count),
+            _ => {}
+        }
+        match buf.get_mut(count) {
+            Some(space) => {
+                *space = ch;
+                count += 1;
+            }
+            None => dropped = true,
+        }
+        prev = Some(ch);
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
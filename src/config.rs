@@ -0,0 +1,358 @@
+//! # Serial port configuration
+//!
+//! Types describing how a serial port is configured - the baud rate and the
+//! shape of each character frame - and traits for ports which can change
+//! their configuration at run time.
+
+use core::fmt;
+
+use crate::delay::StopwatchUs;
+use crate::MutBlockingTx;
+
+/// A baud rate, in bits per second.
+///
+/// The common rates are available as associated constants, but any rate can
+/// be represented - which is useful when describing what the hardware
+/// actually achieved after clock rounding.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Baud(pub u32);
+
+impl Baud {
+    /// 1200 bits per second.
+    pub const B1200: Baud = Baud(1200);
+    /// 2400 bits per second.
+    pub const B2400: Baud = Baud(2400);
+    /// 4800 bits per second.
+    pub const B4800: Baud = Baud(4800);
+    /// 9600 bits per second.
+    pub const B9600: Baud = Baud(9600);
+    /// 19200 bits per second.
+    pub const B19200: Baud = Baud(19200);
+    /// 38400 bits per second.
+    pub const B38400: Baud = Baud(38400);
+    /// 57600 bits per second.
+    pub const B57600: Baud = Baud(57600);
+    /// 115200 bits per second.
+    pub const B115200: Baud = Baud(115_200);
+    /// 230400 bits per second.
+    pub const B230400: Baud = Baud(230_400);
+    /// 460800 bits per second.
+    pub const B460800: Baud = Baud(460_800);
+    /// 921600 bits per second.
+    pub const B921600: Baud = Baud(921_600);
+}
+
+/// The number of data bits in each character.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DataBits {
+    /// Five data bits.
+    Five,
+    /// Six data bits.
+    Six,
+    /// Seven data bits.
+    Seven,
+    /// Eight data bits.
+    Eight,
+    /// Nine data bits.
+    Nine,
+}
+
+/// The parity bit added to each character, if any.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Parity {
+    /// No parity bit.
+    None,
+    /// The parity bit makes the number of set bits odd.
+    Odd,
+    /// The parity bit makes the number of set bits even.
+    Even,
+    /// The parity bit is always set.
+    Mark,
+    /// The parity bit is always clear.
+    Space,
+}
+
+/// The number of stop bits after each character.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StopBits {
+    /// One stop bit.
+    One,
+    /// One and a half stop bits.
+    OnePointFive,
+    /// Two stop bits.
+    Two,
+}
+
+/// How the flow of data is controlled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FlowControl {
+    /// No flow control.
+    None,
+    /// Hardware flow control, using the RTS and CTS lines.
+    RtsCts,
+    /// Software flow control, using XON and XOFF characters.
+    XonXoff,
+}
+
+/// The complete configuration of a serial port.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SerialConfig {
+    /// The baud rate.
+    pub baud: Baud,
+    /// The number of data bits per character.
+    pub data_bits: DataBits,
+    /// The parity bit, if any.
+    pub parity: Parity,
+    /// The number of stop bits per character.
+    pub stop_bits: StopBits,
+    /// The flow control in use.
+    pub flow_control: FlowControl,
+}
+
+/// The baud rate tolerance used by `SerialConfig::verify_against_actual`,
+/// in parts per million (i.e. 2%).
+pub const DEFAULT_BAUD_TOLERANCE_PPM: u32 = 20_000;
+
+/// Describes how the configuration a port actually used differs from the
+/// one that was requested.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ConfigMismatch {
+    /// The configuration which was requested.
+    pub expected: SerialConfig,
+    /// The configuration which was actually applied.
+    pub actual: SerialConfig,
+    /// Which part of the configuration didn't match.
+    pub reason: &'static str,
+}
+
+impl SerialConfig {
+    /// 9600 baud, eight data bits, no parity, one stop bit, no flow control.
+    pub fn standard_9600_8n1() -> SerialConfig {
+        SerialConfig {
+            baud: Baud::B9600,
+            ..SerialConfig::standard_115200_8n1()
+        }
+    }
+
+    /// 115200 baud, eight data bits, no parity, one stop bit, no flow
+    /// control.
+    pub fn standard_115200_8n1() -> SerialConfig {
+        SerialConfig {
+            baud: Baud::B115200,
+            data_bits: DataBits::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+            flow_control: FlowControl::None,
+        }
+    }
+
+    /// Check the configuration a HAL actually applied against this
+    /// (requested) configuration. The baud rate may be out by up to
+    /// `DEFAULT_BAUD_TOLERANCE_PPM`, but everything else must match
+    /// exactly.
+    pub fn verify_against_actual(&self, actual: &SerialConfig) -> Result<(), ConfigMismatch> {
+        self.verify_against_actual_with_tolerance(actual, DEFAULT_BAUD_TOLERANCE_PPM)
+    }
+
+    /// As `verify_against_actual`, but with the given baud rate tolerance, in
+    /// parts per million.
+    pub fn verify_against_actual_with_tolerance(&self,
+                                                actual: &SerialConfig,
+                                                tolerance_ppm: u32)
+                                                -> Result<(), ConfigMismatch> {
+        let reason = if !baud_within(self.baud, actual.baud, tolerance_ppm) {
+            Some("baud rate out of tolerance")
+        } else if self.data_bits != actual.data_bits {
+            Some("data bits differ")
+        } else if self.parity != actual.parity {
+            Some("parity differs")
+        } else if self.stop_bits != actual.stop_bits {
+            Some("stop bits differ")
+        } else if self.flow_control != actual.flow_control {
+            Some("flow control differs")
+        } else {
+            None
+        };
+        match reason {
+            Some(reason) => {
+                Err(ConfigMismatch {
+                    expected: *self,
+                    actual: *actual,
+                    reason,
+                })
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for SerialConfig {
+    /// The default is 115200 8N1 with no flow control.
+    fn default() -> SerialConfig {
+        SerialConfig::standard_115200_8n1()
+    }
+}
+
+/// Implementors of this trait can change their baud rate at run time, e.g.
+/// for autobaud detection.
+pub trait BaudRate {
+    /// The error type returned if a function fails.
+    type Error;
+
+    /// Change the baud rate. Implementations should pick the closest rate
+    /// the hardware can achieve, and fail only if that is unusably far out.
+    fn set_baud_rate(&mut self, baud: Baud) -> Result<(), Self::Error>;
+}
+
+/// Implementors of this trait can change their whole character frame at run
+/// time, not just the baud rate.
+///
+/// (This isn't called `SerialConfig` as that name is taken by the struct
+/// describing a complete configuration.)
+pub trait ConfigureFrame: BaudRate {
+    /// Change the number of data bits in each character.
+    fn set_word_length(&mut self, bits: DataBits) -> Result<(), Self::Error>;
+
+    /// Change the parity bit added to each character.
+    fn set_parity(&mut self, parity: Parity) -> Result<(), Self::Error>;
+
+    /// Change the number of stop bits after each character.
+    fn set_stop_bits(&mut self, stop: StopBits) -> Result<(), Self::Error>;
+}
+
+/// Implementors of this trait can detect a silent gap in the received
+/// data, as used to mark the end of a frame by Modbus RTU (3.5 characters)
+/// and others. See `line::InterCharacterTimeoutRx` for reading frames
+/// delimited this way.
+pub trait InterCharacterTimeout {
+    /// The error type returned if a function fails.
+    type Error;
+
+    /// Set how long the line must be idle after a character, measured in
+    /// character times at the current baud rate and frame format, before a
+    /// gap is reported. Implementations should round up to the nearest
+    /// period the hardware can time.
+    fn set_inter_character_timeout(&mut self, chars: f32) -> Result<(), Self::Error>;
+}
+
+/// The ways in which `verify_baud_by_timing` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BaudVerifyError<E> {
+    /// The measured baud rate was further from the expected rate than the
+    /// tolerance allows. A measurement too short to time gives a `measured`
+    /// of `u32::MAX`.
+    OutOfTolerance {
+        /// The baud rate which was expected.
+        expected: u32,
+        /// The baud rate which was measured.
+        measured: u32,
+    },
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Measure the baud rate a port is really running at, e.g. to catch a
+/// crystal or PLL which is off frequency in manufacturing test.
+///
+/// An 0x55 (alternating ones and zeros) is sent and timed with `timer`,
+/// from the `putc` to the end of the `flush`, assuming a ten bit (8N1)
+/// frame. The port's `flush` must wait for the octet to be shifted out, and
+/// at high baud rates the time taken by the calls themselves, and the
+/// resolution of `timer`, start to matter.
+///
+/// Returns the measured baud rate if it is within `tolerance_ppm` parts per
+/// million of `expected_baud`.
+pub fn verify_baud_by_timing<T, C>(tx: &mut T,
+                                   timer: &mut C,
+                                   expected_baud: u32,
+                                   tolerance_ppm: u32)
+                                   -> Result<u32, BaudVerifyError<T::Error>>
+    where T: ?Sized + MutBlockingTx,
+          C: ?Sized + StopwatchUs
+{
+    // Don't time anything which was already waiting to go.
+    tx.flush().map_err(BaudVerifyError::Inner)?;
+    timer.start();
+    tx.putc(0x55).map_err(BaudVerifyError::Inner)?;
+    tx.flush().map_err(BaudVerifyError::Inner)?;
+    let elapsed = u64::from(timer.elapsed_us());
+    let measured = (10 * 1_000_000 + elapsed / 2)
+        .checked_div(elapsed)
+        .map_or(u32::MAX, |baud| baud.min(u64::from(u32::MAX)) as u32);
+    if baud_within(Baud(expected_baud), Baud(measured), tolerance_ppm) {
+        Ok(measured)
+    } else {
+        Err(BaudVerifyError::OutOfTolerance {
+            expected: expected_baud,
+            measured,
+        })
+    }
+}
+
+impl fmt::Display for DataBits {
+    /// Shows the number of data bits, e.g. `8`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bits = match *self {
+            DataBits::Five => "5",
+            DataBits::Six => "6",
+            DataBits::Seven => "7",
+            DataBits::Eight => "8",
+            DataBits::Nine => "9",
+        };
+        f.write_str(bits)
+    }
+}
+
+impl fmt::Display for Parity {
+    /// Shows the parity as a single letter, e.g. `N` or `E`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let letter = match *self {
+            Parity::None => "N",
+            Parity::Odd => "O",
+            Parity::Even => "E",
+            Parity::Mark => "M",
+            Parity::Space => "S",
+        };
+        f.write_str(letter)
+    }
+}
+
+impl fmt::Display for StopBits {
+    /// Shows the number of stop bits, e.g. `1` or `1.5`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bits = match *self {
+            StopBits::One => "1",
+            StopBits::OnePointFive => "1.5",
+            StopBits::Two => "2",
+        };
+        f.write_str(bits)
+    }
+}
+
+impl fmt::Display for SerialConfig {
+    /// Shows the configuration in the usual notation, e.g. `115200 8N1`.
+    /// The flow control isn't part of that notation, so it is left out -
+    /// use the `Debug` output if you need the whole configuration.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "{} {}{}{}",
+               self.baud.0,
+               self.data_bits,
+               self.parity,
+               self.stop_bits)
+    }
+}
+
+/// Returns true if `actual` is within `tolerance_ppm` parts per million of
+/// `expected`.
+fn baud_within(expected: Baud, actual: Baud, tolerance_ppm: u32) -> bool {
+    let expected = u64::from(expected.0);
+    let actual = u64::from(actual.0);
+    actual.abs_diff(expected) * 1_000_000 <= expected * u64::from(tolerance_ppm)
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,162 @@
+//! Running commands typed at a console.
+
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// A command handler. It is given the rest of the line after the command
+/// name (with leading whitespace removed) and the port, so it can reply.
+pub type CommandHandler<T> = fn(&[u8], &mut T) -> Result<(), <T as MutBlockingTx>::Error>;
+
+/// What `CommandDispatcher::dispatch` did with a line.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DispatchResult {
+    /// The line matched a command, and its handler ran successfully.
+    Handled,
+    /// The first word of the line didn't match any registered command.
+    Unknown,
+    /// The line was empty, or only whitespace.
+    Empty,
+}
+
+/// The ways in which `CommandDispatcher::dispatch` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DispatchError<E> {
+    /// The line was longer than the buffer. It was read and discarded.
+    LineTooLong,
+    /// The port, or the command's handler, returned an error.
+    Inner(E),
+}
+
+/// Wraps a port and runs the command named by each line received, from a
+/// table of up to `N` commands. Lines of up to `LINE` octets (80 by
+/// default) can be read.
+///
+/// A line ends with `\r`, `\n` or `\r\n`. Its first whitespace separated
+/// word is the command name.
+///
+/// ```
+/// use embedded_serial::MutBlockingTx;
+/// use embedded_serial::console::{CommandDispatcher, DispatchResult};
+/// use embedded_serial::error::NoError;
+/// use embedded_serial::testing::MockSerial;
+///
+/// fn echo(args: &[u8], port: &mut MockSerial<'static>) -> Result<(), NoError> {
+///     port.puts(args).map_err(|(_, e)| e)
+/// }
+///
+/// let port = MockSerial::new()
+///     .respond_with(b"echo hi\n")
+///     .expect_write(b"hi")
+///     .respond_with(b"bogus\n");
+/// let mut shell = CommandDispatcher::<_, 4>::new(port);
+/// assert!(shell.register(b"echo", echo));
+/// assert_eq!(shell.dispatch(), Ok(DispatchResult::Handled));
+/// assert_eq!(shell.dispatch(), Ok(DispatchResult::Unknown));
+/// shell.into_inner().assert_complete();
+/// ```
+pub struct CommandDispatcher<T, const N: usize, const LINE: usize = 80>
+    where T: MutBlockingTx
+{
+    inner: T,
+    commands: [Option<(&'static [u8], CommandHandler<T>)>; N],
+    // The last line ended with `\r`, so a `\n` straight after it is ignored.
+    skip_lf: bool,
+}
+
+impl<T, const N: usize, const LINE: usize> CommandDispatcher<T, N, LINE>
+    where T: MutBlockingTx + MutBlockingRx<Error = <T as MutBlockingTx>::Error>
+{
+    /// Wrap a port, with no commands registered.
+    pub fn new(inner: T) -> CommandDispatcher<T, N, LINE> {
+        CommandDispatcher {
+            inner,
+            commands: [None; N],
+            skip_lf: false,
+        }
+    }
+
+    /// Add a command. Returns false, and doesn't add it, if all `N` slots
+    /// are taken. If two commands have the same name, the first one
+    /// registered is used.
+    pub fn register(&mut self, name: &'static [u8], handler: CommandHandler<T>) -> bool {
+        match self.commands.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some((name, handler));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Read a line, blocking until it is complete, and run the matching
+    /// command.
+    pub fn dispatch(&mut self)
+                    -> Result<DispatchResult, DispatchError<<T as MutBlockingTx>::Error>> {
+        let mut buf = [0u8; LINE];
+        let len = self.read_line(&mut buf)?;
+        let line = &buf[..len];
+        let start = match line.iter().position(|ch| !is_whitespace(*ch)) {
+            Some(start) => start,
+            None => return Ok(DispatchResult::Empty),
+        };
+        let line = &line[start..];
+        let end = line.iter().position(|ch| is_whitespace(*ch)).unwrap_or(line.len());
+        let (name, rest) = line.split_at(end);
+        let args_start = rest.iter().position(|ch| !is_whitespace(*ch)).unwrap_or(rest.len());
+        let handler = self.commands
+                          .iter()
+                          .flatten()
+                          .find(|(command, _)| *command == name)
+                          .map(|(_, handler)| *handler);
+        match handler {
+            Some(handler) => {
+                handler(&rest[args_start..], &mut self.inner).map_err(DispatchError::Inner)?;
+                Ok(DispatchResult::Handled)
+            }
+            None => Ok(DispatchResult::Unknown),
+        }
+    }
+
+    /// Read one line into `buf`, without its line ending.
+    fn read_line(&mut self,
+                 buf: &mut [u8; LINE])
+                 -> Result<usize, DispatchError<<T as MutBlockingTx>::Error>> {
+        let mut len = 0;
+        let mut too_long = false;
+        loop {
+            let ch = self.inner.getc().map_err(DispatchError::Inner)?;
+            let skip_lf = core::mem::replace(&mut self.skip_lf, false);
+            match ch {
+                b'\n' if skip_lf => {}
+                b'\r' | b'\n' => {
+                    self.skip_lf = ch == b'\r';
+                    break;
+                }
+                _ if len < LINE => {
+                    buf[len] = ch;
+                    len += 1;
+                }
+                _ => too_long = true,
+            }
+        }
+        if too_long {
+            Err(DispatchError::LineTooLong)
+        } else {
+            Ok(len)
+        }
+    }
+}
+
+fn is_whitespace(ch: u8) -> bool {
+    matches!(ch, b' ' | b'\t')
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
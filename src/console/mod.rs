@@ -0,0 +1,18 @@
+//! # Console
+//!
+//! Building blocks for text consoles and command line interfaces on a
+//! serial port.
+
+mod dispatch;
+mod readline;
+mod tokenizer;
+
+pub use self::dispatch::{CommandDispatcher, CommandHandler, DispatchError, DispatchResult};
+pub use self::readline::ReadlineSerial;
+pub use self::tokenizer::{TokenizerError, TokenizerRx};
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,84 @@
+//! Line editing for an interactive console.
+
+use crate::{MutBlockingTx, MutNonBlockingRx};
+
+const BACKSPACE: u8 = 0x08;
+const DEL: u8 = 0x7F;
+const BEL: u8 = 0x07;
+
+/// Wraps a port connected to a terminal, and collects what the user types
+/// into an `N` octet line buffer.
+///
+/// Everything typed is echoed. Backspace (0x08) or DEL (0x7F) removes the
+/// last character, and sends `\b \b` to rub it out on the terminal. A line
+/// ends with `\r`, `\n` or `\r\n`, and `\r\n` is echoed. Characters typed
+/// once the buffer is full are dropped, and BEL (0x07) is sent instead.
+pub struct ReadlineSerial<T, const N: usize> {
+    inner: T,
+    buffer: [u8; N],
+    len: usize,
+    // The last line ended with `\r`, so a `\n` straight after it is ignored.
+    skip_lf: bool,
+}
+
+impl<T, const N: usize> ReadlineSerial<T, N>
+    where T: MutBlockingTx + MutNonBlockingRx<Error = <T as MutBlockingTx>::Error>
+{
+    /// Wrap a port, with an empty line.
+    pub fn new(inner: T) -> ReadlineSerial<T, N> {
+        ReadlineSerial {
+            inner,
+            buffer: [0; N],
+            len: 0,
+            skip_lf: false,
+        }
+    }
+
+    /// The line typed so far.
+    pub fn partial_line(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+
+    /// Release the wrapped port. Any partial line is lost.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Read and handle whatever has been typed, without blocking. Returns
+    /// the line, without its line ending, once one is complete; the next
+    /// call starts a new line. Returns `Ok(None)` if a line hasn't yet been
+    /// completed.
+    pub fn poll_line(&mut self) -> Result<Option<&[u8]>, <T as MutBlockingTx>::Error> {
+        while let Some(ch) = self.inner.getc_try()? {
+            let skip_lf = core::mem::replace(&mut self.skip_lf, false);
+            match ch {
+                b'\n' if skip_lf => {}
+                b'\r' | b'\n' => {
+                    self.skip_lf = ch == b'\r';
+                    self.inner.puts(b"\r\n").map_err(|(_, e)| e)?;
+                    let len = core::mem::replace(&mut self.len, 0);
+                    return Ok(Some(&self.buffer[..len]));
+                }
+                BACKSPACE | DEL => {
+                    if self.len > 0 {
+                        self.len -= 1;
+                        self.inner.puts(b"\x08 \x08").map_err(|(_, e)| e)?;
+                    }
+                }
+                _ if self.len == N => self.inner.putc(BEL)?,
+                _ => {
+                    self.buffer[self.len] = ch;
+                    self.len += 1;
+                    self.inner.putc(ch)?;
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
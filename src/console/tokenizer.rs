@@ -0,0 +1,108 @@
+//! Splitting received text into whitespace separated tokens.
+
+use crate::MutBlockingRx;
+
+/// The ways in which a `TokenizerRx` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TokenizerError<E> {
+    /// The port returned an error. Any part of a token already read is
+    /// lost.
+    Inner(E),
+}
+
+/// Wraps a `MutBlockingRx` and reads whitespace separated tokens from it.
+///
+/// Space, tab, `\r` and `\n` count as whitespace.
+///
+/// ```
+/// use embedded_serial::console::TokenizerRx;
+/// use embedded_serial::testing::SliceRx;
+///
+/// let mut rx = TokenizerRx::new(SliceRx::new(b"  led on\r\n"));
+/// let mut buf = [0; 2];
+/// assert_eq!(rx.read_token(&mut buf), Ok(2));
+/// assert!(rx.was_truncated());
+/// assert_eq!(rx.read_token(&mut buf), Ok(1));
+/// assert!(!rx.was_truncated());
+/// assert_eq!(rx.read_token(&mut buf), Ok(2));
+/// assert_eq!(&buf, b"on");
+/// ```
+pub struct TokenizerRx<T> {
+    inner: T,
+    // The octet read to find out whether a token carried on past the end of
+    // the buffer.
+    pending: Option<u8>,
+    truncated: bool,
+}
+
+impl<T> TokenizerRx<T>
+    where T: MutBlockingRx
+{
+    /// Wrap a receiver.
+    pub fn new(inner: T) -> TokenizerRx<T> {
+        TokenizerRx {
+            inner,
+            pending: None,
+            truncated: false,
+        }
+    }
+
+    /// Returns true if the last token read didn't fit in the buffer. The
+    /// rest of it is returned by the next `read_token`.
+    pub fn was_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Release the wrapped receiver. The octet after a truncated token, if
+    /// one was read, is returned too.
+    pub fn into_inner(self) -> (T, Option<u8>) {
+        (self.inner, self.pending)
+    }
+
+    /// Skip any whitespace, then read a token into `buf`, blocking until
+    /// whitespace follows it or `buf` is full. Returns the length of the
+    /// token.
+    ///
+    /// After a truncated token this carries on with the rest of it, without
+    /// skipping anything first.
+    pub fn read_token(&mut self, buf: &mut [u8]) -> Result<usize, TokenizerError<T::Error>> {
+        let mut ch = match self.pending.take() {
+            Some(ch) => ch,
+            None => loop {
+                let ch = self.getc()?;
+                if !is_whitespace(ch) {
+                    break ch;
+                }
+            },
+        };
+        self.truncated = false;
+        let mut len = 0;
+        loop {
+            if len == buf.len() {
+                self.pending = Some(ch);
+                self.truncated = true;
+                return Ok(len);
+            }
+            buf[len] = ch;
+            len += 1;
+            ch = self.getc()?;
+            if is_whitespace(ch) {
+                return Ok(len);
+            }
+        }
+    }
+
+    fn getc(&mut self) -> Result<u8, TokenizerError<T::Error>> {
+        self.inner.getc().map_err(TokenizerError::Inner)
+    }
+}
+
+fn is_whitespace(ch: u8) -> bool {
+    matches!(ch, b' ' | b'\t' | b'\r' | b'\n')
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
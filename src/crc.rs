@@ -0,0 +1,66 @@
+//! Checksum algorithms shared by the framing adapters and protocols.
+
+/// Update a Dallas/Maxim CRC-8 (polynomial 0x31, reflected, initial value
+/// zero) with one octet.
+pub(crate) fn crc8_maxim(crc: u8, octet: u8) -> u8 {
+    let mut crc = crc ^ octet;
+    for _ in 0..8 {
+        crc = if crc & 0x01 != 0 {
+            (crc >> 1) ^ 0x8C
+        } else {
+            crc >> 1
+        };
+    }
+    crc
+}
+
+/// Update a CRC-16 with polynomial 0x1021, not reflected, with one octet.
+/// Starting from zero gives CRC-16/XMODEM, and starting from 0xFFFF gives
+/// CRC-16/CCITT-FALSE.
+pub(crate) fn crc16_ccitt(crc: u16, octet: u8) -> u16 {
+    let mut crc = crc ^ (u16::from(octet) << 8);
+    for _ in 0..8 {
+        crc = if crc & 0x8000 != 0 {
+            (crc << 1) ^ 0x1021
+        } else {
+            crc << 1
+        };
+    }
+    crc
+}
+
+/// Update a CRC-16 with polynomial 0x8005, reflected, with one octet.
+/// Starting from zero gives CRC-16/ARC (also known as CRC-16-IBM), and
+/// starting from 0xFFFF gives CRC-16/MODBUS.
+pub(crate) fn crc16_ibm(crc: u16, octet: u8) -> u16 {
+    let mut crc = crc ^ u16::from(octet);
+    for _ in 0..8 {
+        crc = if crc & 0x0001 != 0 {
+            (crc >> 1) ^ 0xA001
+        } else {
+            crc >> 1
+        };
+    }
+    crc
+}
+
+/// Update a CRC-32 with polynomial 0x04C11DB7, reflected, with one octet.
+/// Starting from 0xFFFF_FFFF and inverting the result gives the CRC-32 used
+/// by Ethernet and zip.
+pub(crate) fn crc32_ieee(crc: u32, octet: u8) -> u32 {
+    let mut crc = crc ^ u32::from(octet);
+    for _ in 0..8 {
+        crc = if crc & 0x0000_0001 != 0 {
+            (crc >> 1) ^ 0xEDB8_8320
+        } else {
+            crc >> 1
+        };
+    }
+    crc
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
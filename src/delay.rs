@@ -0,0 +1,47 @@
+//! # Delays
+//!
+//! Minimal delay and timer traits, so adapters which need to wait (or to
+//! measure how long something took) can be given whatever timer the
+//! platform has.
+
+/// Implementors of this trait can block for a given number of
+/// microseconds.
+pub trait DelayUs {
+    /// Block for at least `us` microseconds.
+    fn delay_us(&mut self, us: u32);
+}
+
+impl<D> DelayUs for &mut D
+    where D: ?Sized + DelayUs
+{
+    fn delay_us(&mut self, us: u32) {
+        (**self).delay_us(us)
+    }
+}
+
+/// Implementors of this trait can measure elapsed time in microseconds.
+pub trait StopwatchUs {
+    /// Start timing from zero.
+    fn start(&mut self);
+
+    /// The number of microseconds since `start` was called.
+    fn elapsed_us(&mut self) -> u32;
+}
+
+impl<S> StopwatchUs for &mut S
+    where S: ?Sized + StopwatchUs
+{
+    fn start(&mut self) {
+        (**self).start()
+    }
+
+    fn elapsed_us(&mut self) -> u32 {
+        (**self).elapsed_us()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,153 @@
+//! # DMX512
+//!
+//! DMX512 is sent over RS-485 at 250 kbaud, 8N2. A universe is sent as a
+//! break (at least 88 µs), the mark after break (at least 8 µs), a start
+//! code (0x00 for dimmer data) and then up to 512 channel values.
+
+use crate::line::{BreakRx, BreakTx};
+use crate::MutBlockingRx;
+
+/// The number of channels in a full universe.
+pub const DMX_CHANNELS: usize = 512;
+
+/// The start code of a universe of dimmer levels.
+pub const DMX_START_CODE: u8 = 0x00;
+
+/// The length of the break which starts a universe, in bit-times: 22 bits
+/// at 250 kbaud is the 88 µs minimum.
+pub const DMX_BREAK_BITS: u16 = 22;
+
+/// The ways in which sending or receiving a DMX512 universe can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DmxError<E> {
+    /// A universe arrived with a start code other than
+    /// `DMX_START_CODE` (e.g. 0xCC for RDM). The rest of it is skipped by
+    /// the next `recv_universe`.
+    UnexpectedStartCode(u8),
+    /// The next break arrived after only this many channels. Senders may
+    /// send short universes, so this isn't necessarily a fault; the
+    /// channels received are in the buffer.
+    Short {
+        /// The number of channels received.
+        channels: usize,
+    },
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Wraps a port which can send a break, and sends DMX512 universes on it.
+///
+/// The mark after break is the time between `send_break` returning and the
+/// start code's start bit. If the port doesn't leave at least 8 µs there
+/// itself, use `with_mab_delay` to give a function which waits that long.
+pub struct DmxSerial<T, F = fn()> {
+    inner: T,
+    mab_delay: F,
+}
+
+impl<T> DmxSerial<T>
+    where T: BreakTx
+{
+    /// Wrap a port, relying on it to time the mark after break.
+    pub fn new(inner: T) -> DmxSerial<T> {
+        DmxSerial::with_mab_delay(inner, no_delay)
+    }
+}
+
+impl<T, F> DmxSerial<T, F>
+    where T: BreakTx,
+          F: Fn()
+{
+    /// Wrap a port, calling `mab_delay` between the break and the start
+    /// code.
+    pub fn with_mab_delay(inner: T, mab_delay: F) -> DmxSerial<T, F> {
+        DmxSerial { inner, mab_delay }
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Send a full universe: the break, the mark after break, the start
+    /// code and all 512 channels.
+    pub fn send_universe(&mut self,
+                         channels: &[u8; DMX_CHANNELS])
+                         -> Result<(), DmxError<T::Error>> {
+        self.inner.send_break(DMX_BREAK_BITS).map_err(DmxError::Inner)?;
+        (self.mab_delay)();
+        self.inner.putc(DMX_START_CODE).map_err(DmxError::Inner)?;
+        self.inner.puts(&channels[..]).map_err(|(_, e)| DmxError::Inner(e))
+    }
+}
+
+/// The default mark after break delay - none at all.
+fn no_delay() {}
+
+/// Wraps a port which can detect a break, and receives DMX512 universes on
+/// it.
+///
+/// The port must not hand the break itself to `getc` as an octet, and must
+/// report it through `BreakRx` by the time the octet after it (the start
+/// code) has been read.
+pub struct DmxRx<T> {
+    inner: T,
+    // The start code read when a short universe was cut off by a break.
+    start_code: Option<u8>,
+}
+
+impl<T> DmxRx<T>
+    where T: MutBlockingRx + BreakRx
+{
+    /// Wrap a port.
+    pub fn new(inner: T) -> DmxRx<T> {
+        DmxRx {
+            inner,
+            start_code: None,
+        }
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Wait for the next break, then read the universe which follows it
+    /// into `buf`, blocking until all 512 channels have arrived.
+    pub fn recv_universe(&mut self,
+                         buf: &mut [u8; DMX_CHANNELS])
+                         -> Result<(), DmxError<T::Error>> {
+        let start_code = match self.start_code.take() {
+            Some(code) => code,
+            None => self.wait_for_break()?,
+        };
+        if start_code != DMX_START_CODE {
+            return Err(DmxError::UnexpectedStartCode(start_code));
+        }
+        for (channels, space) in buf.iter_mut().enumerate() {
+            let ch = self.inner.getc().map_err(DmxError::Inner)?;
+            if self.inner.last_break_seen().is_some() {
+                self.start_code = Some(ch);
+                return Err(DmxError::Short { channels });
+            }
+            *space = ch;
+        }
+        Ok(())
+    }
+
+    /// Discard octets until one arrives after a break, and return it.
+    fn wait_for_break(&mut self) -> Result<u8, DmxError<T::Error>> {
+        loop {
+            let ch = self.inner.getc().map_err(DmxError::Inner)?;
+            if self.inner.last_break_seen().is_some() {
+                return Ok(ch);
+            }
+        }
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
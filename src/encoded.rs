@@ -0,0 +1,156 @@
+//! # Pluggable encodings
+//!
+//! `EncodedSerial` wraps a port and passes everything sent through an
+//! `Encoder`, and everything received through a `Decoder`. Implement those
+//! two traits to layer your own encoding over any port.
+
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// Converts outgoing octets into their encoded form.
+pub trait Encoder {
+    /// Encode a single octet, writing the result to `out`.
+    fn encode_byte<T>(&mut self, byte: u8, out: &mut T) -> Result<(), T::Error>
+        where T: ?Sized + MutBlockingTx;
+
+    /// Finish the current frame, writing any buffered data and frame
+    /// trailer to `out`.
+    fn end_frame<T>(&mut self, out: &mut T) -> Result<(), T::Error>
+        where T: ?Sized + MutBlockingTx;
+}
+
+/// The result of passing one received octet to a `Decoder`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DecodeStatus {
+    /// More octets are needed before anything can be decoded.
+    Pending,
+    /// A decoded octet.
+    Byte(u8),
+    /// The end of a frame.
+    EndOfFrame,
+    /// The encoded data was invalid.
+    Invalid,
+}
+
+/// Converts incoming encoded octets back into data.
+pub trait Decoder {
+    /// Process one received octet.
+    fn decode_byte(&mut self, byte: u8) -> DecodeStatus;
+}
+
+/// The ways in which receiving through an `EncodedSerial` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EncodedError<E> {
+    /// The decoder rejected the received data.
+    Invalid,
+    /// A frame was longer than the buffer provided. The frame was read and
+    /// discarded.
+    Overflow,
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Wraps a port, encoding what is sent with `Enc` and decoding what is
+/// received with `Dec`.
+pub struct EncodedSerial<T, Enc, Dec> {
+    inner: T,
+    encoder: Enc,
+    decoder: Dec,
+}
+
+impl<T, Enc, Dec> EncodedSerial<T, Enc, Dec> {
+    /// Wrap a port with the given encoder and decoder.
+    pub fn new(inner: T, encoder: Enc, decoder: Dec) -> EncodedSerial<T, Enc, Dec> {
+        EncodedSerial {
+            inner,
+            encoder,
+            decoder,
+        }
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, Enc, Dec> EncodedSerial<T, Enc, Dec>
+    where T: MutBlockingTx,
+          Enc: Encoder
+{
+    /// Finish the frame currently being sent.
+    pub fn end_frame(&mut self) -> Result<(), T::Error> {
+        self.encoder.end_frame(&mut self.inner)
+    }
+}
+
+impl<T, Enc, Dec> EncodedSerial<T, Enc, Dec>
+    where T: MutBlockingRx,
+          Dec: Decoder
+{
+    /// Read and decode a complete frame into `buffer`, returning the number
+    /// of octets in the frame. A frame too long for `buffer` is read to its
+    /// end and then reported as `EncodedError::Overflow`.
+    pub fn gets_frame(&mut self, buffer: &mut [u8]) -> Result<usize, EncodedError<T::Error>> {
+        let mut count = 0;
+        let mut overflow = false;
+        loop {
+            let ch = self.inner.getc().map_err(EncodedError::Inner)?;
+            match self.decoder.decode_byte(ch) {
+                DecodeStatus::Pending => {}
+                DecodeStatus::Byte(octet) => match buffer.get_mut(count) {
+                    Some(space) => {
+                        *space = octet;
+                        count += 1;
+                    }
+                    None => overflow = true,
+                },
+                DecodeStatus::EndOfFrame if overflow => return Err(EncodedError::Overflow),
+                DecodeStatus::EndOfFrame => return Ok(count),
+                DecodeStatus::Invalid => return Err(EncodedError::Invalid),
+            }
+        }
+    }
+}
+
+impl<T, Enc, Dec> MutBlockingTx for EncodedSerial<T, Enc, Dec>
+    where T: MutBlockingTx,
+          Enc: Encoder
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.encoder.encode_byte(ch, &mut self.inner)
+    }
+
+    /// Flush the wrapped port. This doesn't end the current frame - use
+    /// `end_frame` for that.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+impl<T, Enc, Dec> MutBlockingRx for EncodedSerial<T, Enc, Dec>
+    where T: MutBlockingRx,
+          Dec: Decoder
+{
+    type Error = EncodedError<T::Error>;
+
+    /// Returns the next decoded octet. Frame boundaries are skipped over;
+    /// use `gets_frame` if you need them.
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        loop {
+            let ch = self.inner.getc().map_err(EncodedError::Inner)?;
+            match self.decoder.decode_byte(ch) {
+                DecodeStatus::Pending | DecodeStatus::EndOfFrame => {}
+                DecodeStatus::Byte(octet) => return Ok(octet),
+                DecodeStatus::Invalid => return Err(EncodedError::Invalid),
+            }
+        }
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,126 @@
+//! # Errors
+//!
+//! Most UARTs flag line errors - framing, parity, overrun and so on - in a
+//! status register. The traits here let a driver report those errors in a
+//! common form, and let generic code get the receiver going again
+//! afterwards.
+//!
+//! It also has `NoError`, the error type for ports which can't fail.
+
+use core::convert::Infallible;
+use core::fmt;
+
+/// A line error reported by the UART hardware.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HardwareError {
+    /// A character didn't end with a valid stop bit.
+    Framing,
+    /// A character had the wrong parity.
+    Parity,
+    /// A character arrived before the previous one had been read, and one of
+    /// them was lost.
+    Overrun,
+    /// Noise was detected on the line while a character was received.
+    Noise,
+    /// The line was held low for longer than a character time.
+    Break,
+}
+
+/// Implementors of this trait can report line errors detected by the
+/// hardware.
+pub trait ErrorDetection {
+    /// Returns the most recent line error, if any, and clears it, so that
+    /// the same error is not reported twice.
+    fn take_error(&mut self) -> Option<HardwareError>;
+}
+
+/// Implementors of this trait can bring the receiver back to a usable state
+/// after a line error.
+pub trait ErrorRecovery {
+    /// Clear any error condition in the hardware, discarding any received
+    /// data which may have been corrupted by it.
+    fn recover(&mut self);
+}
+
+/// Implemented by error types which can say whether the operation which
+/// failed is worth trying again, e.g. because a buffer was only briefly
+/// full. Used by `adapters::RetrierTx`.
+pub trait RetriableError {
+    /// Returns true if retrying the operation might succeed.
+    fn is_retriable(&self) -> bool;
+}
+
+/// Implemented by error types which can say whether the port which returned
+/// them has failed for good, e.g. because the link has gone down. Used by
+/// `adapters::FalloverSerial`.
+pub trait FatalError {
+    /// Returns true if the port shouldn't be used again.
+    fn is_fatal(&self) -> bool;
+}
+
+/// The ways in which `SerialRxWithTimeoutExt::recv_with_retry` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RecvRetryError<E> {
+    /// Every attempt was spoiled by a line error.
+    Exhausted {
+        /// The number of attempts made (saturating at 255).
+        attempts: u8,
+        /// The line error which spoiled the final attempt.
+        last_error: HardwareError,
+    },
+    /// The port returned an error which wasn't a line error.
+    Inner(E),
+}
+
+/// The error type for a port which can't fail, as in `type Error = NoError`.
+///
+/// This is a stable stand-in for the never type `!`. It has no values, so a
+/// `Result<T, NoError>` is always `Ok`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NoError {}
+
+impl NoError {
+    /// Convert to any type at all. As there are no `NoError` values, this can
+    /// never actually be called - but it lets you `map_err` a
+    /// `Result<T, NoError>` into whatever error type you need, since a
+    /// blanket `From<NoError>` impl for every type isn't possible.
+    pub fn into_any<T>(self) -> T {
+        match self {}
+    }
+}
+
+impl RetriableError for NoError {
+    fn is_retriable(&self) -> bool {
+        match *self {}
+    }
+}
+
+impl FatalError for NoError {
+    fn is_fatal(&self) -> bool {
+        match *self {}
+    }
+}
+
+impl fmt::Display for NoError {
+    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl From<Infallible> for NoError {
+    fn from(never: Infallible) -> NoError {
+        match never {}
+    }
+}
+
+impl From<NoError> for Infallible {
+    fn from(never: NoError) -> Infallible {
+        match never {}
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
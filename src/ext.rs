@@ -0,0 +1,1116 @@
+//! # Extension traits
+//!
+//! The core traits in this crate are kept small so they are easy to
+//! implement. The convenience functions built on top of them - hex dumps,
+//! fixed-width integer fields, null-terminated strings and so on - live in
+//! the extension traits here instead, and are available on every port once
+//! you import them:
+//!
+//! ```
+//! use embedded_serial::ext::SerialExt;
+//! ```
+
+use core::fmt;
+
+use crate::adapters::{FmtWrite, ImmutTx};
+use crate::at::AtResponse;
+use crate::codec;
+use crate::error::{ErrorDetection, ErrorRecovery, RecvRetryError};
+use crate::nb;
+use crate::{ImmutBlockingRx, ImmutBlockingRxWithTimeout, ImmutBlockingTx,
+            ImmutBlockingTxWithTimeout, ImmutNonBlockingTx, ImmutNonBlockingTxWithTimeout,
+            MutBlockingRx, MutBlockingRxWithTimeout, MutBlockingTx, MutBlockingTxWithTimeout,
+            MutNonBlockingRx, MutNonBlockingTx};
+
+/// Convenience functions for anything which implements `MutBlockingTx`.
+pub trait SerialExt: MutBlockingTx {
+    /// Write formatted text to the UART, e.g.
+    /// `uart.puts_fmt(format_args!("code: {}\r\n", code))`.
+    ///
+    /// If a `Display` (or similar) impl fails part way through, the rest of
+    /// the text is lost but `Ok(())` is still returned, as there is no
+    /// `Self::Error` to report.
+    fn puts_fmt(&mut self, args: fmt::Arguments) -> Result<(), Self::Error> {
+        put_fmt(self, args)
+    }
+
+    /// Write `val` as an `N` octet little-endian field, padding with zeros
+    /// if the value needs fewer than `N` octets.
+    ///
+    /// If `val` does not fit in `N` octets, nothing is sent and you get
+    /// `Err((0, FieldOverflow.into()))`.
+    fn puts_zeropad_le<const N: usize>(&mut self, val: u64) -> Result<(), (usize, Self::Error)>
+        where Self::Error: From<FieldOverflow>
+    {
+        if N < 8 && (val >> (8 * N)) != 0 {
+            return Err((0, FieldOverflow.into()));
+        }
+        put_field(self, val.to_le_bytes(), 0x00, N, false)
+    }
+
+    /// Write `val` as an `N` octet big-endian field, padding with zeros
+    /// if the value needs fewer than `N` octets.
+    ///
+    /// If `val` does not fit in `N` octets, nothing is sent and you get
+    /// `Err((0, FieldOverflow.into()))`.
+    fn puts_zeropad_be<const N: usize>(&mut self, val: u64) -> Result<(), (usize, Self::Error)>
+        where Self::Error: From<FieldOverflow>
+    {
+        if N < 8 && (val >> (8 * N)) != 0 {
+            return Err((0, FieldOverflow.into()));
+        }
+        put_field(self, val.to_le_bytes(), 0x00, N, true)
+    }
+
+    /// Write `val` as an `N` octet two's complement little-endian field,
+    /// sign-extending if the value needs fewer than `N` octets.
+    ///
+    /// If `val` does not fit in `N` octets, nothing is sent and you get
+    /// `Err((0, FieldOverflow.into()))`.
+    fn puts_zeropad_le_signed<const N: usize>(&mut self,
+                                              val: i64)
+                                              -> Result<(), (usize, Self::Error)>
+        where Self::Error: From<FieldOverflow>
+    {
+        if !signed_fits(val, N) {
+            return Err((0, FieldOverflow.into()));
+        }
+        let pad = if val < 0 { 0xFF } else { 0x00 };
+        put_field(self, val.to_le_bytes(), pad, N, false)
+    }
+
+    /// Write `val` as an `N` octet two's complement big-endian field,
+    /// sign-extending if the value needs fewer than `N` octets.
+    ///
+    /// If `val` does not fit in `N` octets, nothing is sent and you get
+    /// `Err((0, FieldOverflow.into()))`.
+    fn puts_zeropad_be_signed<const N: usize>(&mut self,
+                                              val: i64)
+                                              -> Result<(), (usize, Self::Error)>
+        where Self::Error: From<FieldOverflow>
+    {
+        if !signed_fits(val, N) {
+            return Err((0, FieldOverflow.into()));
+        }
+        let pad = if val < 0 { 0xFF } else { 0x00 };
+        put_field(self, val.to_le_bytes(), pad, N, true)
+    }
+
+    /// Write `s` followed by a null octet.
+    /// If this returns `Ok(())`, all the data (`s.len() + 1` octets) was sent.
+    /// Otherwise you get number of octets sent and the error.
+    fn puts_cstring(&mut self, s: &[u8]) -> Result<(), (usize, Self::Error)> {
+        self.puts(s)?;
+        self.putc(0x00).map_err(|e| (s.len(), e))
+    }
+
+    /// Write a string slice followed by a null octet, as `puts_cstring`.
+    fn puts_cstr_str(&mut self, s: &str) -> Result<(), (usize, Self::Error)> {
+        self.puts_cstring(s.as_bytes())
+    }
+
+    /// Write `data`, but only if it is valid UTF-8. The check is done before
+    /// anything is sent, so if you get `Utf8TxError::InvalidUtf8` then no
+    /// octets were sent.
+    fn write_utf8_checked(&mut self, data: &[u8]) -> Result<(), Utf8TxError<Self::Error>> {
+        core::str::from_utf8(data).map_err(Utf8TxError::InvalidUtf8)?;
+        self.puts(data).map_err(Utf8TxError::Tx)
+    }
+
+    /// Write `value` as an unsigned LEB128 varint: seven bits per octet,
+    /// least significant first, with the top bit set on every octet but the
+    /// last. Between one and five octets are sent.
+    fn puts_varint_leb128_u32(&mut self, value: u32) -> Result<(), (usize, Self::Error)> {
+        let mut buf = [0u8; 5];
+        let mut len = 0;
+        let mut value = value;
+        loop {
+            let octet = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                buf[len] = octet;
+                len += 1;
+                break;
+            }
+            buf[len] = octet | 0x80;
+            len += 1;
+        }
+        self.puts(&buf[..len])
+    }
+
+    /// Write `data` as ASCII hex, two upper case characters per octet, with
+    /// no separators.
+    /// If this returns `Ok(())`, all the data was sent.
+    /// Otherwise you get number of characters sent and the error.
+    fn puts_hex(&mut self, data: &[u8]) -> Result<(), (usize, Self::Error)> {
+        for (idx, octet) in data.iter().enumerate() {
+            self.putc(codec::HEX_DIGITS_UPPER[usize::from(octet >> 4)])
+                .map_err(|e| (idx * 2, e))?;
+            self.putc(codec::HEX_DIGITS_UPPER[usize::from(octet & 0x0F)])
+                .map_err(|e| (idx * 2 + 1, e))?;
+        }
+        Ok(())
+    }
+
+    /// Write `data` as a human readable hex dump, sixteen octets per line.
+    /// Each line gives the offset, the octets in hex and then the octets as
+    /// ASCII (with `.` for anything unprintable), and ends with `\r\n`.
+    ///
+    /// Empty data produces no output.
+    fn puts_hex_dump<I>(&mut self, data: &I) -> Result<(), Self::Error>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        for (line, chunk) in data.as_ref().chunks(crate::codec::HEX_DUMP_WIDTH).enumerate() {
+            crate::codec::put_hex_dump_line(self, line * crate::codec::HEX_DUMP_WIDTH, chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Write a structure whose fields have been serialised in big-endian
+    /// order. If this returns `Ok(())`, all the data was sent. Otherwise you
+    /// get number of octets sent and the error.
+    fn write_struct_be<T>(&mut self, val: &T) -> Result<(), (usize, Self::Error)>
+        where T: ?Sized + BigEndianFields
+    {
+        self.puts(val.fields_be())
+    }
+
+    /// Write a structure whose fields have been serialised in little-endian
+    /// order. If this returns `Ok(())`, all the data was sent. Otherwise you
+    /// get number of octets sent and the error.
+    fn write_struct_le<T>(&mut self, val: &T) -> Result<(), (usize, Self::Error)>
+        where T: ?Sized + LittleEndianFields
+    {
+        self.puts(val.fields_le())
+    }
+
+    /// Write `val` as ASCII decimal, with no leading zeros or sign. Returns
+    /// the number of characters written (between one and ten).
+    fn write_ascii_decimal_u32(&mut self, val: u32) -> Result<usize, Self::Error> {
+        let mut buf = [0u8; codec::DECIMAL_BUF_LEN];
+        let digits = codec::format_decimal(val, false, &mut buf);
+        for digit in digits {
+            self.putc(*digit)?;
+        }
+        Ok(digits.len())
+    }
+
+    /// Write `value` as ASCII decimal, with no leading zeros.
+    fn puts_u8_decimal(&mut self, value: u8) -> Result<(), (usize, Self::Error)> {
+        self.puts_u32_decimal(u32::from(value))
+    }
+
+    /// Write `value` as ASCII decimal, with no leading zeros.
+    fn puts_u16_decimal(&mut self, value: u16) -> Result<(), (usize, Self::Error)> {
+        self.puts_u32_decimal(u32::from(value))
+    }
+
+    /// Write `value` as ASCII decimal, with no leading zeros. Unlike
+    /// `write_ascii_decimal_u32`, the digits are formatted first and sent
+    /// with a single `puts`.
+    fn puts_u32_decimal(&mut self, value: u32) -> Result<(), (usize, Self::Error)> {
+        let mut buf = [0u8; codec::DECIMAL_BUF_LEN];
+        self.puts(codec::format_decimal(value, false, &mut buf))
+    }
+
+    /// Write `value` as ASCII decimal, with no leading zeros and a `-` if it
+    /// is negative.
+    fn puts_i32_decimal(&mut self, value: i32) -> Result<(), (usize, Self::Error)> {
+        let mut buf = [0u8; codec::DECIMAL_BUF_LEN];
+        self.puts(codec::format_decimal(value.unsigned_abs(), value < 0, &mut buf))
+    }
+
+    /// Exactly `puts`, but takes a `&str` so string literals don't need
+    /// to be written as byte strings.
+    #[inline]
+    fn puts_str(&mut self, s: &str) -> Result<(), (usize, Self::Error)> {
+        self.puts(s.as_bytes())
+    }
+}
+
+impl<T> SerialExt for T where T: ?Sized + MutBlockingTx {}
+
+/// Convenience functions for anything which implements
+/// `MutBlockingTxWithTimeout`.
+pub trait SerialTxWithTimeoutExt: MutBlockingTxWithTimeout {
+    /// Exactly `puts_wait`, but takes a `&str` so string literals don't need
+    /// to be written as byte strings.
+    #[inline]
+    fn puts_str_wait(&mut self,
+                     s: &str,
+                     timeout: &Self::Timeout)
+                     -> Result<usize, (usize, Self::Error)> {
+        self.puts_wait(s.as_bytes(), timeout)
+    }
+}
+
+impl<T> SerialTxWithTimeoutExt for T where T: ?Sized + MutBlockingTxWithTimeout {}
+
+/// Convenience functions for anything which implements `MutBlockingRx`.
+pub trait SerialRxExt: MutBlockingRx {
+    /// Read a Type-Length-Value record: one octet of tag, a BER encoded
+    /// length of between one and four octets, then that many octets of
+    /// value, which are read into `buf`.
+    ///
+    /// If the value won't fit in `buf` you get `TlvError::BufferTooSmall`
+    /// and the value octets are left unread.
+    fn read_tlv<'b>(&mut self, buf: &'b mut [u8]) -> Result<Tlv<'b>, TlvError<Self::Error>> {
+        let tag = self.getc().map_err(TlvError::Inner)?;
+        let first = self.getc().map_err(TlvError::Inner)?;
+        let length = if first & 0x80 == 0 {
+            usize::from(first)
+        } else {
+            // Long form - the bottom bits give the number of length octets
+            // which follow. The indefinite form (zero octets) isn't
+            // supported.
+            let num_octets = first & 0x7F;
+            if num_octets == 0 || num_octets > 3 {
+                return Err(TlvError::BerLengthTooLong);
+            }
+            let mut length: usize = 0;
+            for _ in 0..num_octets {
+                let octet = self.getc().map_err(TlvError::Inner)?;
+                length = (length << 8) | usize::from(octet);
+            }
+            length
+        };
+        if length > buf.len() {
+            return Err(TlvError::BufferTooSmall { needed: length });
+        }
+        let value = &mut buf[..length];
+        self.gets(value).map_err(|e| TlvError::Inner(e.1))?;
+        Ok(Tlv {
+            tag,
+            length,
+            value,
+        })
+    }
+
+    /// Read `2 * bin_buf.len()` octets of ASCII hex (in either case) and
+    /// decode them into `bin_buf`. Returns the number of octets decoded.
+    ///
+    /// Reading stops at the first octet which isn't a hex digit.
+    fn gets_hex_into(&mut self, bin_buf: &mut [u8]) -> Result<usize, HexDecodeError<Self::Error>> {
+        for space in bin_buf.iter_mut() {
+            let mut octet = 0;
+            for _ in 0..2 {
+                let ch = self.getc().map_err(HexDecodeError::Inner)?;
+                let nibble = crate::codec::hex_value(ch).ok_or(HexDecodeError::InvalidNibble(ch))?;
+                octet = (octet << 4) | nibble;
+            }
+            *space = octet;
+        }
+        Ok(bin_buf.len())
+    }
+
+    /// Read pairs of ASCII hex characters (in either case) and decode them
+    /// into `out`, until `out` is full or a character which isn't a hex digit
+    /// arrives. That character is discarded. Returns the number of octets
+    /// decoded.
+    ///
+    /// You get `HexDecodeError::OddLength` if the hex digits stop half way
+    /// through an octet.
+    fn gets_hex(&mut self, out: &mut [u8]) -> Result<usize, HexDecodeError<Self::Error>> {
+        for (count, space) in out.iter_mut().enumerate() {
+            let ch = self.getc().map_err(HexDecodeError::Inner)?;
+            let high = match codec::hex_value(ch) {
+                Some(nibble) => nibble,
+                None => return Ok(count),
+            };
+            let ch = self.getc().map_err(HexDecodeError::Inner)?;
+            let low = codec::hex_value(ch).ok_or(HexDecodeError::OddLength)?;
+            *space = (high << 4) | low;
+        }
+        Ok(out.len())
+    }
+
+    /// Read an unsigned LEB128 varint, as written by
+    /// `SerialExt::puts_varint_leb128_u32`.
+    ///
+    /// You get `VarIntError::Overflow` if the value won't fit in a `u32`. In
+    /// that case reading stops at the fifth octet, so the rest of the varint
+    /// is left unread.
+    fn gets_varint_leb128_u32(&mut self) -> Result<u32, VarIntError<Self::Error>> {
+        let mut value: u32 = 0;
+        for shift in (0..35).step_by(7) {
+            let octet = self.getc().map_err(VarIntError::Inner)?;
+            let bits = u32::from(octet & 0x7F);
+            if shift == 28 && (octet & 0x80 != 0 || bits > 0x0F) {
+                return Err(VarIntError::Overflow);
+            }
+            value |= bits << shift;
+            if octet & 0x80 == 0 {
+                break;
+            }
+        }
+        Ok(value)
+    }
+
+    /// Read base64 encoded ASCII, in groups of four characters, and decode
+    /// it into `bin_buf`. Returns the number of octets decoded.
+    ///
+    /// Reading stops once `bin_buf` is full, or after a group which ends
+    /// with `=` padding.
+    fn gets_base64_into(&mut self,
+                        bin_buf: &mut [u8])
+                        -> Result<usize, Base64DecodeError<Self::Error>> {
+        let mut written = 0;
+        while written < bin_buf.len() {
+            let mut group = [0u8; 4];
+            for space in group.iter_mut() {
+                *space = self.getc().map_err(Base64DecodeError::Inner)?;
+            }
+            let padding = match (group[2], group[3]) {
+                (b'=', b'=') => 2,
+                (b'=', _) => return Err(Base64DecodeError::InvalidPadding),
+                (_, b'=') => 1,
+                _ => 0,
+            };
+            let mut bits: u32 = 0;
+            for ch in &group[..4 - padding] {
+                let value = crate::codec::base64_value(*ch)
+                    .ok_or(Base64DecodeError::InvalidCharacter(*ch))?;
+                bits = (bits << 6) | u32::from(value);
+            }
+            bits <<= 6 * padding;
+            let decoded = 3 - padding;
+            if written + decoded > bin_buf.len() {
+                return Err(Base64DecodeError::BufferTooSmall { needed: written + decoded });
+            }
+            for (idx, space) in bin_buf[written..written + decoded].iter_mut().enumerate() {
+                *space = (bits >> (16 - 8 * idx)) as u8;
+            }
+            written += decoded;
+            if padding != 0 {
+                break;
+            }
+        }
+        Ok(written)
+    }
+
+    /// Read a null-terminated string into `buf`. Returns the string, not
+    /// including the null.
+    ///
+    /// If `buf` fills up before a null arrives, you get
+    /// `CStringError::BufferFull`.
+    fn gets_cstring<'b>(&mut self, buf: &'b mut [u8]) -> Result<&'b [u8], CStringError<Self::Error>> {
+        for idx in 0..buf.len() {
+            let ch = self.getc().map_err(CStringError::Inner)?;
+            if ch == 0x00 {
+                return Ok(&buf[..idx]);
+            }
+            buf[idx] = ch;
+        }
+        Err(CStringError::BufferFull(buf.len()))
+    }
+
+    /// As `gets_cstring`, but also checks that the string is valid UTF-8.
+    fn gets_cstring_utf8<'b>(&mut self, buf: &'b mut [u8]) -> Result<&'b str, CStringError<Self::Error>> {
+        let data = self.gets_cstring(buf)?;
+        core::str::from_utf8(data).map_err(CStringError::InvalidUtf8)
+    }
+
+    /// Read a response into `buf` until it ends with one of `terminators`,
+    /// such as the `OK\r\n` or `ERROR\r\n` at the end of an AT command
+    /// response. Empty terminators are ignored.
+    ///
+    /// Returns the number of octets read (including the terminator) and the
+    /// index in `terminators` of the one which matched. If several match,
+    /// the first in the list wins.
+    fn read_response_terminated_by(&mut self,
+                                   buf: &mut [u8],
+                                   terminators: &[&[u8]])
+                                   -> Result<(usize, usize), ResponseError<Self::Error>> {
+        for count in 1..=buf.len() {
+            buf[count - 1] = self.getc().map_err(ResponseError::Inner)?;
+            let data = &buf[..count];
+            let found = terminators.iter().position(|t| !t.is_empty() && data.ends_with(t));
+            if let Some(index) = found {
+                return Ok((count, index));
+            }
+        }
+        Err(ResponseError::BufferFull(buf.len()))
+    }
+
+    /// Read an unsigned ASCII decimal number of up to `max_digits` digits.
+    ///
+    /// Reading stops at the first octet which isn't a digit, which is
+    /// discarded, or once `max_digits` digits have been read. You get
+    /// `ParseDecimalError::NoDigitsFound` if the first octet isn't a digit
+    /// and `ParseDecimalError::Overflow` if the number won't fit in a `u32`.
+    fn read_ascii_decimal_u32(&mut self, max_digits: u8) -> Result<u32, ParseDecimalError<Self::Error>> {
+        let mut val: u32 = 0;
+        for count in 0..max_digits {
+            let ch = self.getc().map_err(ParseDecimalError::Inner)?;
+            if !ch.is_ascii_digit() {
+                if count == 0 {
+                    return Err(ParseDecimalError::NoDigitsFound);
+                }
+                return Ok(val);
+            }
+            val = val.checked_mul(10)
+                .and_then(|v| v.checked_add(u32::from(ch - b'0')))
+                .ok_or(ParseDecimalError::Overflow)?;
+        }
+        if max_digits == 0 {
+            return Err(ParseDecimalError::NoDigitsFound);
+        }
+        Ok(val)
+    }
+
+    /// Read an unsigned ASCII decimal number, keeping its digits in `buf`.
+    ///
+    /// Reading stops at the first octet which isn't a digit, which is
+    /// discarded. You get `ParseDecimalError::NoDigitsFound` if the first
+    /// octet isn't a digit, `ParseDecimalError::BufferTooSmall` if the
+    /// digits don't fit in `buf` (they are still all read) and
+    /// `ParseDecimalError::Overflow` if the number won't fit in the result
+    /// type.
+    fn gets_u8_decimal(&mut self, buf: &mut [u8]) -> Result<u8, ParseDecimalError<Self::Error>> {
+        gets_decimal(self, buf, false)
+    }
+
+    /// As `gets_u8_decimal`, but for a `u16`.
+    fn gets_u16_decimal(&mut self, buf: &mut [u8]) -> Result<u16, ParseDecimalError<Self::Error>> {
+        gets_decimal(self, buf, false)
+    }
+
+    /// As `gets_u8_decimal`, but for a `u32`.
+    fn gets_u32_decimal(&mut self, buf: &mut [u8]) -> Result<u32, ParseDecimalError<Self::Error>> {
+        gets_decimal(self, buf, false)
+    }
+
+    /// As `gets_u8_decimal`, but for an `i32`, which may start with a `-`.
+    fn gets_i32_decimal(&mut self, buf: &mut [u8]) -> Result<i32, ParseDecimalError<Self::Error>> {
+        gets_decimal(self, buf, true)
+    }
+
+    /// Read a line of text into `buf`, up to a `\n`. A `\r` just before the
+    /// `\n` is removed too, so both Unix and Windows line endings work.
+    ///
+    /// Returns the length of the line, not including the line ending. If the
+    /// line is too long for `buf`, the rest of it is read and thrown away, up
+    /// to and including the line ending, and you get `Ok(buf.len())`.
+    /// `Err((n, e))` means `n` octets were stored then there was an error.
+    fn read_line(&mut self, buf: &mut [u8]) -> Result<usize, (usize, Self::Error)> {
+        self.read_line_with_ending(buf, LineEnding::Any)
+    }
+
+    /// As `read_line`, but with control over which line ending is expected.
+    fn read_line_with_ending(&mut self,
+                             buf: &mut [u8],
+                             ending: LineEnding)
+                             -> Result<usize, (usize, Self::Error)> {
+        crate::codec::read_line(buf, ending, || self.getc().map(Some))
+    }
+
+    /// Read octets into `buf` until `delimiter` has been read or `buf` is
+    /// full. The delimiter is stored in `buf` too, so check the last octet
+    /// to tell the two apart.
+    ///
+    /// `Ok(n)` gives the number of octets stored, including any delimiter.
+    /// `Err((n, e))` means `n` octets were stored then there was an error.
+    fn gets_until(&mut self,
+                  buf: &mut [u8],
+                  delimiter: u8)
+                  -> Result<usize, (usize, Self::Error)> {
+        for (count, space) in buf.iter_mut().enumerate() {
+            let ch = self.getc().map_err(|e| (count, e))?;
+            *space = ch;
+            if ch == delimiter {
+                return Ok(count + 1);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    /// Read and throw away `count` octets, blocking until that many have been
+    /// read - e.g. to skip a fixed-length preamble without a scratch buffer.
+    /// The error handling is as for `gets`.
+    fn gets_discard(&mut self, count: usize) -> Result<(), (usize, Self::Error)> {
+        for discarded in 0..count {
+            self.getc().map_err(|e| (discarded, e))?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> SerialRxExt for T where T: ?Sized + MutBlockingRx {}
+
+/// Convenience functions for anything which implements
+/// `MutBlockingRxWithTimeout`.
+pub trait SerialRxWithTimeoutExt: MutBlockingRxWithTimeout {
+    /// As `SerialRxExt::read_line`, but if a timeout occurs the partial line
+    /// read so far is returned. The timeout applies to each octet
+    /// individually.
+    fn read_line_wait(&mut self,
+                      buf: &mut [u8],
+                      timeout: &Self::Timeout)
+                      -> Result<usize, (usize, Self::Error)> {
+        self.read_line_with_ending_wait(buf, LineEnding::Any, timeout)
+    }
+
+    /// As `SerialRxExt::read_line_with_ending`, but if a timeout occurs the
+    /// partial line read so far is returned.
+    fn read_line_with_ending_wait(&mut self,
+                                  buf: &mut [u8],
+                                  ending: LineEnding,
+                                  timeout: &Self::Timeout)
+                                  -> Result<usize, (usize, Self::Error)> {
+        crate::codec::read_line(buf, ending, || self.getc_wait(timeout))
+    }
+
+    /// Read lines ending with `\n` or `\r\n` until one equals `sentinel`,
+    /// and return the lines which came before it. The timeout applies to
+    /// each octet individually.
+    ///
+    /// Up to `N` lines are kept, each of which must be valid UTF-8 and no
+    /// longer than `L` octets (so `sentinel` must be no longer than `L`
+    /// either). If a line breaks one of these rules, reading carries on
+    /// until the sentinel arrives, so the next response starts in the right
+    /// place, and then the first error found is returned.
+    fn read_response_lines<const L: usize, const N: usize>(&mut self,
+                                                           sentinel: &str,
+                                                           timeout: &Self::Timeout)
+                                                           -> Result<AtResponse<N, L>,
+                                                                     ResponseLinesError<Self::Error>> {
+        let mut lines = AtResponse::new();
+        let mut error = None;
+        let mut buf = [0u8; L];
+        loop {
+            let mut len = 0;
+            let mut prev = None;
+            loop {
+                let ch = match self.getc_wait(timeout).map_err(ResponseLinesError::Inner)? {
+                    None => return Err(ResponseLinesError::Timeout),
+                    Some(b'\n') => break,
+                    Some(ch) => ch,
+                };
+                if let Some(space) = buf.get_mut(len) {
+                    *space = ch;
+                }
+                len += 1;
+                prev = Some(ch);
+            }
+            if prev == Some(b'\r') {
+                len -= 1;
+            }
+            let line_error = if len > L {
+                Some(ResponseLinesError::LineTooLong(len))
+            } else if &buf[..len] == sentinel.as_bytes() {
+                return error.map_or(Ok(lines), Err);
+            } else if core::str::from_utf8(&buf[..len]).is_err() {
+                Some(ResponseLinesError::Utf8Error)
+            } else if !lines.push(&buf[..len]) {
+                Some(ResponseLinesError::TooManyLines)
+            } else {
+                None
+            };
+            error = error.or(line_error);
+        }
+    }
+
+    /// Fill the given buffer as `gets_wait` does, retrying if the hardware
+    /// reports a line error (such as a noise-induced framing error) through
+    /// `ErrorDetection::take_error`.
+    ///
+    /// Before each retry, the receiver is recovered with
+    /// `ErrorRecovery::recover` and the read starts again from the start of
+    /// `buffer`. Up to `max_retries` retries are made before giving up with
+    /// `RecvRetryError::Exhausted`. An error from the port itself isn't
+    /// retried, and is returned as `RecvRetryError::Inner`.
+    ///
+    /// If the result is `Ok(size)` but `size < buffer.len()`, you had a
+    /// timeout.
+    fn recv_with_retry(&mut self,
+                       buffer: &mut [u8],
+                       timeout: &Self::Timeout,
+                       max_retries: u8)
+                       -> Result<usize, RecvRetryError<Self::Error>>
+        where Self: ErrorDetection + ErrorRecovery
+    {
+        let mut retries: u8 = 0;
+        loop {
+            let result = self.gets_wait(buffer, timeout);
+            let last_error = match self.take_error() {
+                Some(last_error) => last_error,
+                None => return result.map_err(|(_, e)| RecvRetryError::Inner(e)),
+            };
+            if retries == max_retries {
+                return Err(RecvRetryError::Exhausted {
+                    attempts: retries.saturating_add(1),
+                    last_error,
+                });
+            }
+            self.recover();
+            retries += 1;
+        }
+    }
+
+    /// Read octets into `buf` until `delimiter` has been read, `buf` is full
+    /// or a timeout occurs. The delimiter is stored in `buf` too, so check
+    /// the last octet to tell these apart. The timeout applies to each
+    /// octet individually.
+    ///
+    /// `Ok(n)` gives the number of octets stored, including any delimiter.
+    /// `Err((n, e))` means `n` octets were stored then there was an error.
+    fn gets_until_wait(&mut self,
+                       buf: &mut [u8],
+                       delimiter: u8,
+                       timeout: &Self::Timeout)
+                       -> Result<usize, (usize, Self::Error)> {
+        for (count, space) in buf.iter_mut().enumerate() {
+            let ch = match self.getc_wait(timeout).map_err(|e| (count, e))? {
+                None => return Ok(count),
+                Some(ch) => ch,
+            };
+            *space = ch;
+            if ch == delimiter {
+                return Ok(count + 1);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    /// Read and throw away `count` octets, as `gets_discard` does, stopping
+    /// early if a timeout occurs. The timeout applies to each octet
+    /// individually.
+    ///
+    /// If the result is `Ok(size)` but `size < count`, you had a timeout.
+    fn gets_discard_wait(&mut self,
+                         count: usize,
+                         timeout: &Self::Timeout)
+                         -> Result<usize, (usize, Self::Error)> {
+        for discarded in 0..count {
+            if self.getc_wait(timeout).map_err(|e| (discarded, e))?.is_none() {
+                return Ok(discarded);
+            }
+        }
+        Ok(count)
+    }
+}
+
+impl<T> SerialRxWithTimeoutExt for T where T: ?Sized + MutBlockingRxWithTimeout {}
+
+/// Convenience functions for anything which implements `MutNonBlockingTx`.
+pub trait SerialNonBlockingTxExt: MutNonBlockingTx {
+    /// As `putc_try`, but a full FIFO/buffer is reported as
+    /// `nb::Error::WouldBlock`, for use with `nb_block!`.
+    fn putc_nb(&mut self, ch: u8) -> nb::Result<(), Self::Error> {
+        match self.putc_try(ch)? {
+            Some(_) => Ok(()),
+            None => Err(nb::Error::WouldBlock),
+        }
+    }
+
+    /// Exactly `puts_try`, but takes a `&str` so string literals don't need
+    /// to be written as byte strings.
+    #[inline]
+    fn puts_str_try(&mut self, s: &str) -> Result<usize, (usize, Self::Error)> {
+        self.puts_try(s.as_bytes())
+    }
+}
+
+impl<T> SerialNonBlockingTxExt for T where T: ?Sized + MutNonBlockingTx {}
+
+/// Convenience functions for anything which implements `MutNonBlockingRx`.
+pub trait SerialNonBlockingRxExt: MutNonBlockingRx {
+    /// As `getc_try`, but an empty FIFO/buffer is reported as
+    /// `nb::Error::WouldBlock`, for use with `nb_block!`.
+    fn getc_nb(&mut self) -> nb::Result<u8, Self::Error> {
+        self.getc_try()?.ok_or(nb::Error::WouldBlock)
+    }
+}
+
+impl<T> SerialNonBlockingRxExt for T where T: ?Sized + MutNonBlockingRx {}
+
+/// Convenience functions for anything which implements `ImmutBlockingTx`.
+pub trait ImmutSerialExt: ImmutBlockingTx {
+    /// As `SerialExt::puts_fmt`.
+    fn puts_fmt(&self, args: fmt::Arguments) -> Result<(), Self::Error> {
+        put_fmt(ImmutTx(self), args)
+    }
+
+    /// Exactly `puts`, but takes a `&str` so string literals don't need
+    /// to be written as byte strings.
+    #[inline]
+    fn puts_str(&self, s: &str) -> Result<(), (usize, Self::Error)> {
+        self.puts(s.as_bytes())
+    }
+}
+
+impl<T> ImmutSerialExt for T where T: ?Sized + ImmutBlockingTx {}
+
+/// Convenience functions for anything which implements
+/// `ImmutBlockingTxWithTimeout`.
+pub trait ImmutSerialTxWithTimeoutExt: ImmutBlockingTxWithTimeout {
+    /// Exactly `puts_wait`, but takes a `&str` so string literals don't need
+    /// to be written as byte strings.
+    #[inline]
+    fn puts_str_wait(&self,
+                     s: &str,
+                     timeout: &Self::Timeout)
+                     -> Result<usize, (usize, Self::Error)> {
+        self.puts_wait(s.as_bytes(), timeout)
+    }
+}
+
+impl<T> ImmutSerialTxWithTimeoutExt for T where T: ?Sized + ImmutBlockingTxWithTimeout {}
+
+/// Convenience functions for anything which implements `ImmutNonBlockingTx`.
+pub trait ImmutSerialNonBlockingTxExt: ImmutNonBlockingTx {
+    /// Exactly `puts_try`, but takes a `&str` so string literals don't need
+    /// to be written as byte strings.
+    #[inline]
+    fn puts_str_try(&self, s: &str) -> Result<usize, (usize, Self::Error)> {
+        self.puts_try(s.as_bytes())
+    }
+}
+
+impl<T> ImmutSerialNonBlockingTxExt for T where T: ?Sized + ImmutNonBlockingTx {}
+
+/// Convenience functions for anything which implements
+/// `ImmutNonBlockingTxWithTimeout`.
+pub trait ImmutSerialNonBlockingTxWithTimeoutExt: ImmutNonBlockingTxWithTimeout {
+    /// Exactly `puts_try_wait`, but takes a `&str` so string literals don't
+    /// need to be written as byte strings.
+    #[inline]
+    fn puts_str_try_wait(&self,
+                         s: &str,
+                         timeout: &Self::Timeout)
+                         -> Result<usize, (usize, Self::Error)> {
+        self.puts_try_wait(s.as_bytes(), timeout)
+    }
+}
+
+impl<T> ImmutSerialNonBlockingTxWithTimeoutExt for T
+    where T: ?Sized + ImmutNonBlockingTxWithTimeout
+{
+}
+
+/// Convenience functions for anything which implements `ImmutBlockingRx`.
+pub trait ImmutSerialRxExt: ImmutBlockingRx {
+    /// Read octets into `buf` until `delimiter` has been read or `buf` is
+    /// full. The delimiter is stored in `buf` too, so check the last octet
+    /// to tell the two apart.
+    ///
+    /// `Ok(n)` gives the number of octets stored, including any delimiter.
+    /// `Err((n, e))` means `n` octets were stored then there was an error.
+    fn gets_until(&self,
+                  buf: &mut [u8],
+                  delimiter: u8)
+                  -> Result<usize, (usize, Self::Error)> {
+        for (count, space) in buf.iter_mut().enumerate() {
+            let ch = self.getc().map_err(|e| (count, e))?;
+            *space = ch;
+            if ch == delimiter {
+                return Ok(count + 1);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    /// Read and throw away `count` octets, blocking until that many have been
+    /// read - e.g. to skip a fixed-length preamble without a scratch buffer.
+    /// The error handling is as for `gets`.
+    fn gets_discard(&self, count: usize) -> Result<(), (usize, Self::Error)> {
+        for discarded in 0..count {
+            self.getc().map_err(|e| (discarded, e))?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> ImmutSerialRxExt for T where T: ?Sized + ImmutBlockingRx {}
+
+/// Convenience functions for anything which implements
+/// `ImmutBlockingRxWithTimeout`.
+pub trait ImmutSerialRxWithTimeoutExt: ImmutBlockingRxWithTimeout {
+    /// Read octets into `buf` until `delimiter` has been read, `buf` is full
+    /// or a timeout occurs. The delimiter is stored in `buf` too, so check
+    /// the last octet to tell these apart. The timeout applies to each
+    /// octet individually.
+    ///
+    /// `Ok(n)` gives the number of octets stored, including any delimiter.
+    /// `Err((n, e))` means `n` octets were stored then there was an error.
+    fn gets_until_wait(&self,
+                       buf: &mut [u8],
+                       delimiter: u8,
+                       timeout: &Self::Timeout)
+                       -> Result<usize, (usize, Self::Error)> {
+        for (count, space) in buf.iter_mut().enumerate() {
+            let ch = match self.getc_wait(timeout).map_err(|e| (count, e))? {
+                None => return Ok(count),
+                Some(ch) => ch,
+            };
+            *space = ch;
+            if ch == delimiter {
+                return Ok(count + 1);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    /// Read and throw away `count` octets, as `gets_discard` does, stopping
+    /// early if a timeout occurs. The timeout applies to each octet
+    /// individually.
+    ///
+    /// If the result is `Ok(size)` but `size < count`, you had a timeout.
+    fn gets_discard_wait(&self,
+                         count: usize,
+                         timeout: &Self::Timeout)
+                         -> Result<usize, (usize, Self::Error)> {
+        for discarded in 0..count {
+            if self.getc_wait(timeout).map_err(|e| (discarded, e))?.is_none() {
+                return Ok(discarded);
+            }
+        }
+        Ok(count)
+    }
+}
+
+impl<T> ImmutSerialRxWithTimeoutExt for T where T: ?Sized + ImmutBlockingRxWithTimeout {}
+
+/// The line endings `SerialRxExt::read_line_with_ending` can look for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Lines end with `\n`. Any `\r` is kept as part of the line.
+    Lf,
+    /// Lines end with `\r`. Any `\n` is kept as part of the line.
+    Cr,
+    /// Lines end with `\r\n`. A `\r` or `\n` on its own is kept as part of
+    /// the line.
+    CrLf,
+    /// Lines end with `\n` or `\r\n`. This is what `read_line` uses.
+    Any,
+}
+
+/// The error raised when a value is too large for the fixed-width field it
+/// is being written into.
+///
+/// Your `Error` type must implement `From<FieldOverflow>` to use the
+/// `puts_zeropad_*` functions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FieldOverflow;
+
+/// A Type-Length-Value record, as read by `SerialRxExt::read_tlv`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Tlv<'b> {
+    /// The record's tag.
+    pub tag: u8,
+    /// The length of the record's value, in octets.
+    pub length: usize,
+    /// The record's value.
+    pub value: &'b [u8],
+}
+
+/// The ways in which `SerialRxExt::read_tlv` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TlvError<E> {
+    /// The BER length field was longer than four octets, or used the
+    /// indefinite form.
+    BerLengthTooLong,
+    /// The value was longer than the buffer provided.
+    BufferTooSmall {
+        /// The size of buffer the value needs.
+        needed: usize,
+    },
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// The ways in which reading ASCII hex can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HexDecodeError<E> {
+    /// The hex digits stopped half way through an octet.
+    OddLength,
+    /// An octet which isn't a hex digit was received where one was required.
+    InvalidNibble(u8),
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// The ways in which reading base64 can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Base64DecodeError<E> {
+    /// An octet which isn't in the base64 alphabet was received.
+    InvalidCharacter(u8),
+    /// The `=` padding at the end of a group was malformed.
+    InvalidPadding,
+    /// The decoded data was longer than the buffer provided.
+    BufferTooSmall {
+        /// The size of buffer the data needs.
+        needed: usize,
+    },
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// The ways in which reading an ASCII decimal number, with
+/// `SerialRxExt::read_ascii_decimal_u32` or the `gets_*_decimal` functions,
+/// can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseDecimalError<E> {
+    /// The number was too big for the type being read.
+    Overflow,
+    /// The number had more digits than the buffer provided could hold. The
+    /// whole number was read and discarded.
+    BufferTooSmall,
+    /// The first octet read wasn't a digit.
+    NoDigitsFound,
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// The ways in which `SerialRxExt::gets_varint_leb128_u32` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VarIntError<E> {
+    /// The value was too big for the type being read.
+    Overflow,
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// The ways in which `SerialRxExt::read_response_terminated_by` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ResponseError<E> {
+    /// The buffer, of the given size, filled up before any terminator
+    /// arrived.
+    BufferFull(usize),
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// The ways in which `SerialRxWithTimeoutExt::read_response_lines` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ResponseLinesError<E> {
+    /// More lines arrived before the sentinel than there was room for.
+    TooManyLines,
+    /// A line of the given length was too long to keep.
+    LineTooLong(usize),
+    /// A line wasn't valid UTF-8.
+    Utf8Error,
+    /// No octet arrived before the timeout.
+    Timeout,
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// The ways in which reading a null-terminated string can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CStringError<E> {
+    /// The buffer, of the given size, filled up before the null arrived.
+    BufferFull(usize),
+    /// The string was not valid UTF-8.
+    InvalidUtf8(core::str::Utf8Error),
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// The ways in which `SerialExt::write_utf8_checked` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Utf8TxError<E> {
+    /// The data was not valid UTF-8, so nothing was sent.
+    InvalidUtf8(core::str::Utf8Error),
+    /// The port returned an error, after sending the given number of
+    /// octets.
+    Tx((usize, E)),
+}
+
+/// Implemented by structures which can present their fields as a sequence of
+/// big-endian octets, for use with `SerialExt::write_struct_be`.
+pub trait BigEndianFields {
+    /// The structure's fields, each serialised in big-endian order.
+    fn fields_be(&self) -> &[u8];
+}
+
+/// Implemented by structures which can present their fields as a sequence of
+/// little-endian octets, for use with `SerialExt::write_struct_le`.
+pub trait LittleEndianFields {
+    /// The structure's fields, each serialised in little-endian order.
+    fn fields_le(&self) -> &[u8];
+}
+
+/// Write formatted text to `tx` through a `FmtWrite`, and return the error
+/// from the port, if there was one.
+fn put_fmt<T>(tx: T, args: fmt::Arguments) -> Result<(), T::Error>
+    where T: MutBlockingTx
+{
+    let mut writer = FmtWrite::new(tx);
+    let _ = fmt::Write::write_fmt(&mut writer, args);
+    writer.take_error().map_or(Ok(()), Err)
+}
+
+/// Read ASCII decimal digits (after a `-`, if `signed`) into `buf` until a
+/// non-digit arrives, then parse them.
+fn gets_decimal<T, N>(rx: &mut T,
+                      buf: &mut [u8],
+                      signed: bool)
+                      -> Result<N, ParseDecimalError<T::Error>>
+    where T: ?Sized + MutBlockingRx,
+          N: core::str::FromStr
+{
+    // Read on to the end of the number even if it doesn't fit, so the next
+    // read doesn't start half way through it.
+    let mut len = 0;
+    loop {
+        let ch = rx.getc().map_err(ParseDecimalError::Inner)?;
+        let sign = signed && len == 0 && ch == b'-';
+        if !ch.is_ascii_digit() && !sign {
+            break;
+        }
+        if let Some(space) = buf.get_mut(len) {
+            *space = ch;
+        }
+        len += 1;
+    }
+    if len > buf.len() {
+        return Err(ParseDecimalError::BufferTooSmall);
+    }
+    let text = &buf[..len];
+    if !text.iter().any(u8::is_ascii_digit) {
+        return Err(ParseDecimalError::NoDigitsFound);
+    }
+    // Only digits (and perhaps a sign) were kept, so parsing can only fail
+    // if the number is out of range.
+    core::str::from_utf8(text)
+        .ok()
+        .and_then(|text| text.parse().ok())
+        .ok_or(ParseDecimalError::Overflow)
+}
+
+/// Returns true if `val` can be held in `width` octets of two's complement.
+fn signed_fits(val: i64, width: usize) -> bool {
+    if width == 0 {
+        val == 0
+    } else if width < 8 {
+        let top = val >> (8 * width - 1);
+        top == 0 || top == -1
+    } else {
+        true
+    }
+}
+
+/// Sends `width` octets of the little-endian `bytes`, using `pad` for any
+/// octets beyond the eighth, in the requested order.
+fn put_field<T>(tx: &mut T,
+                bytes: [u8; 8],
+                pad: u8,
+                width: usize,
+                big_endian: bool)
+                -> Result<(), (usize, T::Error)>
+    where T: ?Sized + MutBlockingTx
+{
+    for count in 0..width {
+        let idx = if big_endian { width - 1 - count } else { count };
+        let octet = if idx < bytes.len() { bytes[idx] } else { pad };
+        tx.putc(octet).map_err(|e| (count, e))?;
+    }
+    Ok(())
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,220 @@
+//! Consistent Overhead Byte Stuffing (COBS) framing.
+//!
+//! COBS removes every zero octet from the data, so that a single zero can
+//! mark the end of each frame. The overhead is at most one octet in every
+//! 254, plus the terminating zero.
+
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// The longest run of non-zero octets a single COBS block can hold.
+const MAX_RUN: usize = 254;
+
+/// Wraps a `MutBlockingTx` and sends each `puts` call as a complete COBS
+/// frame, ending with a zero octet.
+///
+/// The data is encoded straight from the slice passed to `puts`, so no
+/// buffer is needed.
+pub struct CobsEncoder<T> {
+    inner: T,
+}
+
+impl<T> CobsEncoder<T>
+    where T: MutBlockingTx
+{
+    /// Wrap a transmitter.
+    pub fn new(inner: T) -> CobsEncoder<T> {
+        CobsEncoder { inner }
+    }
+
+    /// Release the wrapped transmitter.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingTx for CobsEncoder<T>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    /// Send a frame holding just the one octet.
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.puts(&[ch]).map_err(|(_, e)| e)
+    }
+
+    /// Send `data` as one COBS frame. If this returns `Ok(())`, the whole
+    /// frame was sent. Otherwise you get number of octets of `data` sent and
+    /// the error.
+    fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let data = data.as_ref();
+        let mut start = 0;
+        loop {
+            let end = core::cmp::min(start + MAX_RUN, data.len());
+            let run = data[start..end].iter().position(|octet| *octet == 0).unwrap_or(end - start);
+            self.inner.putc(run as u8 + 1).map_err(|e| (start, e))?;
+            for (idx, octet) in data[start..start + run].iter().enumerate() {
+                self.inner.putc(*octet).map_err(|e| (start + idx, e))?;
+            }
+            start += run;
+            if start == data.len() {
+                break;
+            }
+            if run < MAX_RUN {
+                // Skip the zero, which the decoder puts back.
+                start += 1;
+            }
+        }
+        self.inner.putc(0).map_err(|e| (data.len(), e))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+/// The ways in which receiving through a `CobsDecoder` can fail.
+///
+/// Where given, `consumed` is the number of octets read from the port for
+/// the frame, including the terminating zero. The frame is discarded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CobsError<E> {
+    /// The frame wasn't valid COBS.
+    Malformed {
+        /// The octets read for the frame.
+        consumed: usize,
+    },
+    /// The encoded frame didn't fit in the decoder's buffer.
+    FrameTooLong {
+        /// The octets read for the frame.
+        consumed: usize,
+    },
+    /// The buffer passed to `gets_frame` was too small. The frame is kept,
+    /// and can be read with a bigger buffer or octet by octet.
+    BufferTooSmall {
+        /// The size of buffer the frame needs.
+        needed: usize,
+    },
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Wraps a `MutBlockingRx` and decodes the COBS frames it receives.
+///
+/// Each frame is read into an internal buffer of `N` octets, up to and
+/// including its terminating zero, then decoded. `N` must be big enough for
+/// the longest encoded frame (excluding the zero). Empty frames are skipped.
+pub struct CobsDecoder<T, const N: usize> {
+    inner: T,
+    buffer: [u8; N],
+    len: usize,
+    pos: usize,
+}
+
+impl<T, const N: usize> CobsDecoder<T, N>
+    where T: MutBlockingRx
+{
+    /// Wrap a receiver.
+    pub fn new(inner: T) -> CobsDecoder<T, N> {
+        CobsDecoder {
+            inner,
+            buffer: [0; N],
+            len: 0,
+            pos: 0,
+        }
+    }
+
+    /// Read the rest of the current frame into `buffer`, or the whole of the
+    /// next frame if the current one has been read. Returns the number of
+    /// octets written.
+    pub fn gets_frame(&mut self, buffer: &mut [u8]) -> Result<usize, CobsError<T::Error>> {
+        while self.pos == self.len {
+            self.load_frame()?;
+        }
+        let frame = &self.buffer[self.pos..self.len];
+        let dest = buffer.get_mut(..frame.len())
+            .ok_or(CobsError::BufferTooSmall { needed: frame.len() })?;
+        dest.copy_from_slice(frame);
+        self.pos = self.len;
+        Ok(frame.len())
+    }
+
+    /// Release the wrapped receiver. Any part of a frame not yet read is
+    /// lost.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Read the next frame from the port and decode it, in place.
+    fn load_frame(&mut self) -> Result<(), CobsError<T::Error>> {
+        self.len = 0;
+        self.pos = 0;
+        let mut consumed = 0;
+        loop {
+            let ch = self.inner.getc().map_err(CobsError::Inner)?;
+            consumed += 1;
+            if ch == 0 {
+                break;
+            }
+            if consumed > N {
+                // Too long - keep going to the end of the frame, so we are in
+                // sync for the next one.
+                continue;
+            }
+            self.buffer[consumed - 1] = ch;
+        }
+        if consumed - 1 > N {
+            return Err(CobsError::FrameTooLong { consumed });
+        }
+        self.len = decode_in_place(&mut self.buffer[..consumed - 1])
+            .ok_or(CobsError::Malformed { consumed })?;
+        Ok(())
+    }
+}
+
+impl<T, const N: usize> MutBlockingRx for CobsDecoder<T, N>
+    where T: MutBlockingRx
+{
+    type Error = CobsError<T::Error>;
+
+    /// Returns the next decoded octet. Frame boundaries are skipped over;
+    /// use `gets_frame` if you need them.
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        while self.pos == self.len {
+            self.load_frame()?;
+        }
+        let ch = self.buffer[self.pos];
+        self.pos += 1;
+        Ok(ch)
+    }
+}
+
+/// Decode a COBS frame (without its terminating zero) in place, returning
+/// the decoded length, or `None` if the frame is malformed.
+fn decode_in_place(frame: &mut [u8]) -> Option<usize> {
+    let mut read = 0;
+    let mut write = 0;
+    while read < frame.len() {
+        let code = usize::from(frame[read]);
+        read += 1;
+        let run = code - 1;
+        if read + run > frame.len() {
+            return None;
+        }
+        frame.copy_within(read..read + run, write);
+        read += run;
+        write += run;
+        if run < MAX_RUN && read < frame.len() {
+            frame[write] = 0;
+            write += 1;
+        }
+    }
+    Some(write)
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,117 @@
+//! CRC-16/CCITT-FALSE protected frames.
+//!
+//! The CRC uses polynomial 0x1021, not reflected, with an initial value of
+//! 0xFFFF. It is sent most significant octet first.
+
+use crate::crc::crc16_ccitt;
+use crate::{MutBlockingRx, MutBlockingTx};
+
+const INIT: u16 = 0xFFFF;
+
+/// Wraps a `MutBlockingTx` and keeps a running CRC-16 over everything sent,
+/// which is only sent when you call `flush_crc`.
+pub struct Crc16CcittTx<T> {
+    inner: T,
+    crc: u16,
+}
+
+impl<T> Crc16CcittTx<T>
+    where T: MutBlockingTx
+{
+    /// Wrap a transmitter.
+    pub fn new(inner: T) -> Crc16CcittTx<T> {
+        Crc16CcittTx { inner, crc: INIT }
+    }
+
+    /// The CRC of everything sent since the last `flush_crc`.
+    pub fn crc(&self) -> u16 {
+        self.crc
+    }
+
+    /// Send the two CRC octets, ending the frame, and reset the CRC.
+    pub fn flush_crc(&mut self) -> Result<(), T::Error> {
+        self.inner.putc((self.crc >> 8) as u8)?;
+        self.inner.putc(self.crc as u8)?;
+        self.crc = INIT;
+        Ok(())
+    }
+
+    /// Release the wrapped transmitter.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingTx for Crc16CcittTx<T>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.inner.putc(ch)?;
+        self.crc = crc16_ccitt(self.crc, ch);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+/// Wraps a `MutBlockingRx` and keeps a running CRC-16 over everything
+/// received, to be checked against the CRC octets with `verify_crc`.
+pub struct Crc16CcittRx<T> {
+    inner: T,
+    crc: u16,
+}
+
+impl<T> Crc16CcittRx<T>
+    where T: MutBlockingRx
+{
+    /// Wrap a receiver.
+    pub fn new(inner: T) -> Crc16CcittRx<T> {
+        Crc16CcittRx { inner, crc: INIT }
+    }
+
+    /// The CRC of everything received since the last `verify_crc`.
+    pub fn crc(&self) -> u16 {
+        self.crc
+    }
+
+    /// Read the two CRC octets which end a frame, and return whether they
+    /// match the data received. The CRC is then reset either way.
+    pub fn verify_crc(&mut self) -> Result<bool, T::Error> {
+        let expected = self.crc;
+        self.crc = INIT;
+        let high = self.inner.getc()?;
+        let low = self.inner.getc()?;
+        Ok(u16::from(high) << 8 | u16::from(low) == expected)
+    }
+
+    /// Release the wrapped receiver.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingRx for Crc16CcittRx<T>
+    where T: MutBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        let ch = self.inner.getc()?;
+        self.crc = crc16_ccitt(self.crc, ch);
+        Ok(ch)
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,254 @@
+//! Dallas/Maxim CRC-8 protected frames, as used on the 1-Wire bus.
+//!
+//! The CRC uses polynomial 0x31 (bit-reversed, as 0x8C) with an initial
+//! value of zero.
+
+use crate::crc::crc8_maxim;
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// Wraps a `MutBlockingTx` and follows the data in each `puts` call with a
+/// CRC-8 octet.
+///
+/// The CRC covers every octet sent since the last `reset`, whether by `putc`
+/// or `puts`.
+pub struct Crc8Writer<T> {
+    inner: T,
+    crc: u8,
+}
+
+impl<T> Crc8Writer<T>
+    where T: MutBlockingTx
+{
+    /// Wrap a transmitter.
+    pub fn new(inner: T) -> Crc8Writer<T> {
+        Crc8Writer { inner, crc: 0 }
+    }
+
+    /// Clear the CRC, ready for the next frame.
+    pub fn reset(&mut self) {
+        self.crc = 0;
+    }
+
+    /// The CRC of everything sent since the last `reset`.
+    pub fn crc(&self) -> u8 {
+        self.crc
+    }
+
+    /// Release the wrapped transmitter.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingTx for Crc8Writer<T>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    /// Send an octet and add it to the CRC. No CRC octet is sent.
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.inner.putc(ch)?;
+        self.crc = crc8_maxim(self.crc, ch);
+        Ok(())
+    }
+
+    /// Send `data` and then the CRC octet. If this returns `Ok(())`, all
+    /// `data.len() + 1` octets were sent. Otherwise you get number of octets
+    /// sent and the error.
+    fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let data = data.as_ref();
+        for (count, octet) in data.iter().enumerate() {
+            self.putc(*octet).map_err(|e| (count, e))?;
+        }
+        self.inner.putc(self.crc).map_err(|e| (data.len(), e))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+/// The ways in which `Crc8Reader::verify_frame` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Crc8Error<E> {
+    /// The CRC octet received didn't match the data.
+    CrcMismatch {
+        /// The CRC calculated over the received data.
+        expected: u8,
+        /// The CRC octet which was received.
+        got: u8,
+    },
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Wraps a `MutBlockingRx` and calculates a CRC-8 over everything received,
+/// so the CRC octet at the end of a frame can be checked.
+pub struct Crc8Reader<T> {
+    inner: T,
+    crc: u8,
+}
+
+impl<T> Crc8Reader<T>
+    where T: MutBlockingRx
+{
+    /// Wrap a receiver.
+    pub fn new(inner: T) -> Crc8Reader<T> {
+        Crc8Reader { inner, crc: 0 }
+    }
+
+    /// Clear the CRC, ready for the next frame.
+    pub fn reset(&mut self) {
+        self.crc = 0;
+    }
+
+    /// The CRC of everything received since the last `reset`.
+    pub fn crc(&self) -> u8 {
+        self.crc
+    }
+
+    /// Read the CRC octet which ends a frame and check it against the data
+    /// received since the last `reset`. The CRC is then reset either way.
+    pub fn verify_frame(&mut self) -> Result<(), Crc8Error<T::Error>> {
+        let got = self.inner.getc().map_err(Crc8Error::Inner)?;
+        let expected = self.crc;
+        self.reset();
+        if got == expected {
+            Ok(())
+        } else {
+            Err(Crc8Error::CrcMismatch { expected, got })
+        }
+    }
+
+    /// Release the wrapped receiver.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingRx for Crc8Reader<T>
+    where T: MutBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        let ch = self.inner.getc()?;
+        self.crc = crc8_maxim(self.crc, ch);
+        Ok(ch)
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+/// Wraps a `MutBlockingTx` and keeps a running CRC-8 over everything sent,
+/// which is only sent when you call `flush_crc`.
+///
+/// Unlike `Crc8Writer`, a frame can be built from any number of `putc` and
+/// `puts` calls.
+pub struct Crc8Tx<T> {
+    inner: T,
+    crc: u8,
+}
+
+impl<T> Crc8Tx<T>
+    where T: MutBlockingTx
+{
+    /// Wrap a transmitter.
+    pub fn new(inner: T) -> Crc8Tx<T> {
+        Crc8Tx { inner, crc: 0 }
+    }
+
+    /// The CRC of everything sent since the last `flush_crc`.
+    pub fn crc(&self) -> u8 {
+        self.crc
+    }
+
+    /// Send the CRC octet, ending the frame, and reset the CRC.
+    pub fn flush_crc(&mut self) -> Result<(), T::Error> {
+        self.inner.putc(self.crc)?;
+        self.crc = 0;
+        Ok(())
+    }
+
+    /// Release the wrapped transmitter.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingTx for Crc8Tx<T>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.inner.putc(ch)?;
+        self.crc = crc8_maxim(self.crc, ch);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+/// Wraps a `MutBlockingRx` and keeps a running CRC-8 over everything
+/// received, to be checked against the CRC octet with `verify_crc`.
+pub struct Crc8Rx<T> {
+    inner: T,
+    crc: u8,
+}
+
+impl<T> Crc8Rx<T>
+    where T: MutBlockingRx
+{
+    /// Wrap a receiver.
+    pub fn new(inner: T) -> Crc8Rx<T> {
+        Crc8Rx { inner, crc: 0 }
+    }
+
+    /// The CRC of everything received since the last `verify_crc`.
+    pub fn crc(&self) -> u8 {
+        self.crc
+    }
+
+    /// Read the CRC octet which ends a frame, and return whether it matches
+    /// the data received. The CRC is then reset either way.
+    pub fn verify_crc(&mut self) -> Result<bool, T::Error> {
+        let got = self.inner.getc()?;
+        let expected = self.crc;
+        self.crc = 0;
+        Ok(got == expected)
+    }
+
+    /// Release the wrapped receiver.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingRx for Crc8Rx<T>
+    where T: MutBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        let ch = self.inner.getc()?;
+        self.crc = crc8_maxim(self.crc, ch);
+        Ok(ch)
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        self.inner.bytes_available()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,187 @@
+//! Length-prefixed frames.
+//!
+//! Each frame starts with its payload length, as a `u8`, `u16` or `u32`,
+//! followed by the payload. Optionally, a CRC-16/XMODEM over the length
+//! field and the payload follows.
+
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+
+use crate::crc::crc16_ccitt;
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// The types which can be used for the length field of a `LengthFramed`
+/// frame: `u8`, `u16` and `u32`.
+pub trait LengthPrefix {
+    /// The width of the length field, in octets.
+    const WIDTH: usize;
+    /// The longest payload the length field can describe.
+    const MAX: u32;
+}
+
+impl LengthPrefix for u8 {
+    const WIDTH: usize = 1;
+    const MAX: u32 = 0xFF;
+}
+
+impl LengthPrefix for u16 {
+    const WIDTH: usize = 2;
+    const MAX: u32 = 0xFFFF;
+}
+
+impl LengthPrefix for u32 {
+    const WIDTH: usize = 4;
+    const MAX: u32 = 0xFFFF_FFFF;
+}
+
+/// The order in which the octets of the length field (and CRC) are sent.
+pub trait ByteOrder {
+    /// When a `width` octet value is sent in this order, returns how far
+    /// (in bits) the octet sent at position `index` is shifted in the value.
+    fn shift(width: usize, index: usize) -> usize;
+}
+
+/// Send the most significant octet first. This is the default.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BigEndian;
+
+/// Send the least significant octet first.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LittleEndian;
+
+impl ByteOrder for BigEndian {
+    fn shift(width: usize, index: usize) -> usize {
+        8 * (width - 1 - index)
+    }
+}
+
+impl ByteOrder for LittleEndian {
+    fn shift(_width: usize, index: usize) -> usize {
+        8 * index
+    }
+}
+
+/// The ways in which sending or receiving a length-prefixed frame can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FrameError<E> {
+    /// The payload is too long for the length field, so nothing was sent,
+    /// or the received length doesn't fit in a `usize`.
+    TooLong,
+    /// The received frame was longer than the buffer provided. The payload
+    /// (and CRC) are left unread.
+    BufferTooSmall {
+        /// The size of buffer the frame needs.
+        needed: usize,
+    },
+    /// The CRC received didn't match the frame.
+    CrcMismatch {
+        /// The CRC calculated over the received frame.
+        expected: u16,
+        /// The CRC which was received.
+        got: u16,
+    },
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Wraps a port and sends and receives length-prefixed frames.
+///
+/// `L` is the type of the length field (`u8`, `u16` or `u32`), and `O` the
+/// order its octets are sent in (`BigEndian` by default, or
+/// `LittleEndian`). If `CRC` is true, each frame ends with a CRC-16/XMODEM
+/// over the length field and payload, in the same octet order.
+pub struct LengthFramed<T, L, O = BigEndian, const CRC: bool = false> {
+    inner: T,
+    _format: PhantomData<(L, O)>,
+}
+
+impl<T, L, O, const CRC: bool> LengthFramed<T, L, O, CRC>
+    where L: LengthPrefix,
+          O: ByteOrder
+{
+    /// Wrap a port.
+    pub fn new(inner: T) -> LengthFramed<T, L, O, CRC> {
+        LengthFramed {
+            inner,
+            _format: PhantomData,
+        }
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, L, O, const CRC: bool> LengthFramed<T, L, O, CRC>
+    where T: MutBlockingTx,
+          L: LengthPrefix,
+          O: ByteOrder
+{
+    /// Send `data` as one frame.
+    pub fn write_frame(&mut self, data: &[u8]) -> Result<(), FrameError<T::Error>> {
+        let length = match u32::try_from(data.len()) {
+            Ok(length) if length <= L::MAX => length,
+            _ => return Err(FrameError::TooLong),
+        };
+        let mut crc = 0;
+        for index in 0..L::WIDTH {
+            let octet = (length >> O::shift(L::WIDTH, index)) as u8;
+            self.inner.putc(octet).map_err(FrameError::Inner)?;
+            crc = crc16_ccitt(crc, octet);
+        }
+        for octet in data {
+            self.inner.putc(*octet).map_err(FrameError::Inner)?;
+            crc = crc16_ccitt(crc, *octet);
+        }
+        if CRC {
+            for index in 0..2 {
+                let octet = (crc >> O::shift(2, index)) as u8;
+                self.inner.putc(octet).map_err(FrameError::Inner)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T, L, O, const CRC: bool> LengthFramed<T, L, O, CRC>
+    where T: MutBlockingRx,
+          L: LengthPrefix,
+          O: ByteOrder
+{
+    /// Read one frame into `buf`, returning the payload.
+    pub fn read_frame<'b>(&mut self, buf: &'b mut [u8]) -> Result<&'b [u8], FrameError<T::Error>> {
+        let mut crc = 0;
+        let mut length: u32 = 0;
+        for index in 0..L::WIDTH {
+            let octet = self.inner.getc().map_err(FrameError::Inner)?;
+            crc = crc16_ccitt(crc, octet);
+            length |= u32::from(octet) << O::shift(L::WIDTH, index);
+        }
+        let length = usize::try_from(length).map_err(|_| FrameError::TooLong)?;
+        if length > buf.len() {
+            return Err(FrameError::BufferTooSmall { needed: length });
+        }
+        for space in &mut buf[..length] {
+            *space = self.inner.getc().map_err(FrameError::Inner)?;
+            crc = crc16_ccitt(crc, *space);
+        }
+        if CRC {
+            let mut got: u16 = 0;
+            for index in 0..2 {
+                let octet = self.inner.getc().map_err(FrameError::Inner)?;
+                got |= u16::from(octet) << O::shift(2, index);
+            }
+            if got != crc {
+                return Err(FrameError::CrcMismatch { expected: crc, got });
+            }
+        }
+        Ok(&buf[..length])
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,18 @@
+//! # Framing
+//!
+//! Adapters which add framing or integrity checks on top of a plain octet
+//! stream.
+
+pub mod cobs;
+pub mod crc16;
+pub mod crc8;
+pub mod length;
+pub mod packet;
+pub mod sequence;
+pub mod slip;
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
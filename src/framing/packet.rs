@@ -0,0 +1,247 @@
+//! Binary packets with a magic number, a message ID and a CRC.
+//!
+//! Each packet is sent as:
+//!
+//! | Field   | Octets          |
+//! |---------|-----------------|
+//! | Magic   | 2               |
+//! | ID      | 1               |
+//! | Length  | 2               |
+//! | Payload | Length          |
+//! | CRC     | 2 or 4          |
+//!
+//! Every multi-octet field is sent most significant octet first. The CRC
+//! covers the ID, length and payload.
+
+use core::marker::PhantomData;
+
+use crate::crc::{crc16_ibm, crc32_ieee};
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// The CRC algorithms which can protect a `PacketSerial` packet.
+pub trait PacketCrc {
+    /// The width of the CRC field, in octets.
+    const WIDTH: usize;
+
+    /// The CRC before any octets have been added.
+    fn init() -> u32;
+
+    /// Add one octet to the CRC.
+    fn update(crc: u32, octet: u8) -> u32;
+
+    /// Turn the running CRC into the value which is sent.
+    fn finish(crc: u32) -> u32;
+}
+
+/// CRC-16-IBM (CRC-16/ARC): polynomial 0x8005, reflected, initial value
+/// zero. This is the default.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Crc16Ibm;
+
+/// CRC-32 as used by Ethernet and zip: polynomial 0x04C11DB7, reflected,
+/// initial value and final XOR 0xFFFF_FFFF.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Crc32;
+
+impl PacketCrc for Crc16Ibm {
+    const WIDTH: usize = 2;
+
+    fn init() -> u32 {
+        0
+    }
+
+    fn update(crc: u32, octet: u8) -> u32 {
+        u32::from(crc16_ibm(crc as u16, octet))
+    }
+
+    fn finish(crc: u32) -> u32 {
+        crc
+    }
+}
+
+impl PacketCrc for Crc32 {
+    const WIDTH: usize = 4;
+
+    fn init() -> u32 {
+        0xFFFF_FFFF
+    }
+
+    fn update(crc: u32, octet: u8) -> u32 {
+        crc32_ieee(crc, octet)
+    }
+
+    fn finish(crc: u32) -> u32 {
+        !crc
+    }
+}
+
+/// A packet received by `PacketSerial::recv_packet`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Packet<'b> {
+    /// The message ID.
+    pub id: u8,
+    /// The payload, in the buffer given to `recv_packet`.
+    pub payload: &'b [u8],
+}
+
+/// The ways in which sending or receiving a packet can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PacketError<E> {
+    /// The payload is longer than `MAX_PAYLOAD` octets. When sending,
+    /// nothing was sent. When receiving, the rest of the packet wasn't read;
+    /// the next `recv_packet` looks for a new magic number.
+    TooLong {
+        /// The length of the payload.
+        len: usize,
+    },
+    /// The received packet was longer than the buffer provided. The packet
+    /// was read and discarded.
+    BufferTooSmall {
+        /// The size of buffer the packet needs.
+        needed: usize,
+    },
+    /// The CRC received didn't match the packet.
+    CrcMismatch {
+        /// The CRC calculated over the received packet.
+        expected: u32,
+        /// The CRC which was received.
+        got: u32,
+    },
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Wraps a port and sends and receives packets with payloads of up to
+/// `MAX_PAYLOAD` octets.
+///
+/// `C` picks the CRC (`Crc16Ibm` by default, or `Crc32`), and `MAGIC` the
+/// two octets which start each packet.
+///
+/// ```
+/// use embedded_serial::framing::packet::PacketSerial;
+/// use embedded_serial::testing::Loopback;
+///
+/// let mut port = PacketSerial::<_, 16>::new(Loopback::<32>::new());
+/// port.send_packet(0x42, b"ping").unwrap();
+/// let mut buf = [0; 16];
+/// let packet = port.recv_packet(&mut buf).unwrap();
+/// assert_eq!(packet.id, 0x42);
+/// assert_eq!(packet.payload, b"ping");
+/// ```
+pub struct PacketSerial<T, const MAX_PAYLOAD: usize, C = Crc16Ibm, const MAGIC: u16 = 0xA55A> {
+    inner: T,
+    _crc: PhantomData<C>,
+}
+
+impl<T, const MAX_PAYLOAD: usize, C, const MAGIC: u16> PacketSerial<T, MAX_PAYLOAD, C, MAGIC>
+    where C: PacketCrc
+{
+    const CHECK: () = assert!(MAX_PAYLOAD <= 0xFFFF, "the length field only holds 16 bits");
+
+    /// Wrap a port.
+    pub fn new(inner: T) -> PacketSerial<T, MAX_PAYLOAD, C, MAGIC> {
+        let () = Self::CHECK;
+        PacketSerial {
+            inner,
+            _crc: PhantomData,
+        }
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, const MAX_PAYLOAD: usize, C, const MAGIC: u16> PacketSerial<T, MAX_PAYLOAD, C, MAGIC>
+    where T: MutBlockingTx,
+          C: PacketCrc
+{
+    /// Send `payload` as one packet with the given message ID.
+    pub fn send_packet(&mut self, id: u8, payload: &[u8]) -> Result<(), PacketError<T::Error>> {
+        if payload.len() > MAX_PAYLOAD {
+            return Err(PacketError::TooLong { len: payload.len() });
+        }
+        let len = payload.len() as u16;
+        let mut crc = C::init();
+        for octet in &MAGIC.to_be_bytes() {
+            self.inner.putc(*octet).map_err(PacketError::Inner)?;
+        }
+        let header = [id, (len >> 8) as u8, len as u8];
+        for octet in header.iter().chain(payload) {
+            self.inner.putc(*octet).map_err(PacketError::Inner)?;
+            crc = C::update(crc, *octet);
+        }
+        let crc = C::finish(crc);
+        for index in 0..C::WIDTH {
+            let octet = (crc >> (8 * (C::WIDTH - 1 - index))) as u8;
+            self.inner.putc(octet).map_err(PacketError::Inner)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, const MAX_PAYLOAD: usize, C, const MAGIC: u16> PacketSerial<T, MAX_PAYLOAD, C, MAGIC>
+    where T: MutBlockingRx,
+          C: PacketCrc
+{
+    /// Read one packet into `buf`, blocking until it has arrived. Anything
+    /// received before the magic number is discarded.
+    pub fn recv_packet<'b>(&mut self,
+                           buf: &'b mut [u8])
+                           -> Result<Packet<'b>, PacketError<T::Error>> {
+        let magic = MAGIC.to_be_bytes();
+        let mut prev = None;
+        loop {
+            let octet = self.getc()?;
+            if prev == Some(magic[0]) && octet == magic[1] {
+                break;
+            }
+            prev = Some(octet);
+        }
+        let mut crc = C::init();
+        let mut header = [0; 3];
+        for space in header.iter_mut() {
+            *space = self.getc()?;
+            crc = C::update(crc, *space);
+        }
+        let id = header[0];
+        let length = usize::from(u16::from_be_bytes([header[1], header[2]]));
+        if length > MAX_PAYLOAD {
+            return Err(PacketError::TooLong { len: length });
+        }
+        let too_small = length > buf.len();
+        for idx in 0..length {
+            let octet = self.getc()?;
+            crc = C::update(crc, octet);
+            if let Some(space) = buf.get_mut(idx) {
+                *space = octet;
+            }
+        }
+        let mut got = 0;
+        for _ in 0..C::WIDTH {
+            got = (got << 8) | u32::from(self.getc()?);
+        }
+        let expected = C::finish(crc);
+        if !too_small && got != expected {
+            return Err(PacketError::CrcMismatch { expected, got });
+        }
+        if too_small {
+            return Err(PacketError::BufferTooSmall { needed: length });
+        }
+        Ok(Packet {
+            id,
+            payload: &buf[..length],
+        })
+    }
+
+    fn getc(&mut self) -> Result<u8, PacketError<T::Error>> {
+        self.inner.getc().map_err(PacketError::Inner)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,111 @@
+//! Finding a multi-octet sequence, such as a frame's magic number.
+//!
+//! Many protocols start each frame with a fixed sequence of octets (`CA FE`,
+//! or `+++`). `SequenceDetector` reads from a port until it has seen that
+//! sequence, however the reads happen to split it up.
+
+use crate::MutBlockingRx;
+
+/// The ways in which looking for a sequence can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SequenceError<E> {
+    /// The port returned an error. The octets read so far are remembered,
+    /// so calling `read_until_sequence` again carries on where this call
+    /// stopped.
+    Inner(E),
+}
+
+/// Wraps a `MutBlockingRx` and reads from it until an `N` octet sequence
+/// has been received.
+///
+/// The last `N` octets read are kept in a ring buffer and compared with the
+/// pattern after each octet arrives.
+///
+/// ```
+/// use embedded_serial::framing::sequence::SequenceDetector;
+/// use embedded_serial::testing::SliceRx;
+///
+/// let mut port = SequenceDetector::new(SliceRx::new(b"noise\xCA\xFEframe"));
+/// let mut before = [0; 8];
+/// assert_eq!(port.read_until_sequence(&[0xCA, 0xFE], &mut before), Ok(7));
+/// assert_eq!(&before[..5], b"noise");
+/// assert_eq!(port.into_inner().remaining(), b"frame");
+/// ```
+pub struct SequenceDetector<T, const N: usize> {
+    inner: T,
+    window: [u8; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> SequenceDetector<T, N>
+    where T: MutBlockingRx
+{
+    const CHECK: () = assert!(N >= 1, "a SequenceDetector needs a pattern of at least one octet");
+
+    /// Wrap a receiver.
+    pub fn new(inner: T) -> SequenceDetector<T, N> {
+        let () = Self::CHECK;
+        SequenceDetector {
+            inner,
+            window: [0; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Read octets until the last `N` read match `pattern`, blocking until
+    /// then.
+    ///
+    /// The octets which came before the pattern are stored in `buf`, as many
+    /// as will fit, and any more are discarded; pass an empty `buf` to
+    /// discard them all. Returns the number of octets consumed, including
+    /// the pattern and any octets read by an earlier call which failed.
+    pub fn read_until_sequence(&mut self,
+                               pattern: &[u8; N],
+                               buf: &mut [u8])
+                               -> Result<usize, SequenceError<T::Error>> {
+        // Octets left in the window by an earlier call are counted too, as
+        // they are stored in `buf` (or matched) just like new ones.
+        let mut consumed = self.len;
+        let mut stored = 0;
+        loop {
+            let ch = self.inner.getc().map_err(SequenceError::Inner)?;
+            consumed += 1;
+            if self.len < N {
+                self.window[(self.head + self.len) % N] = ch;
+                self.len += 1;
+            } else {
+                if let Some(space) = buf.get_mut(stored) {
+                    *space = self.window[self.head];
+                    stored += 1;
+                }
+                self.window[self.head] = ch;
+                self.head = (self.head + 1) % N;
+            }
+            if self.len == N && self.matches(pattern) {
+                self.len = 0;
+                return Ok(consumed);
+            }
+        }
+    }
+
+    /// Release the wrapped receiver. Any octets read but not yet matched
+    /// are lost.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn matches(&self, pattern: &[u8; N]) -> bool {
+        pattern
+            .iter()
+            .enumerate()
+            .all(|(idx, octet)| self.window[(self.head + idx) % N] == *octet)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
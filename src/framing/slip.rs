@@ -0,0 +1,189 @@
+//! Serial Line Internet Protocol (SLIP) framing, as described in RFC 1055.
+//!
+//! Each frame is bracketed by END (0xC0) octets. END and ESC (0xDB) octets
+//! in the data are escaped as ESC ESC_END (0xDB 0xDC) and ESC ESC_ESC
+//! (0xDB 0xDD) respectively.
+
+use crate::{MutBlockingRx, MutBlockingTx};
+
+const END: u8 = 0xC0;
+const ESC: u8 = 0xDB;
+const ESC_END: u8 = 0xDC;
+const ESC_ESC: u8 = 0xDD;
+
+/// Wraps a `MutBlockingTx` and sends each `puts` call as a complete SLIP
+/// frame, with an END octet at each end.
+pub struct SlipEncoder<T> {
+    inner: T,
+}
+
+impl<T> SlipEncoder<T>
+    where T: MutBlockingTx
+{
+    /// Wrap a transmitter.
+    pub fn new(inner: T) -> SlipEncoder<T> {
+        SlipEncoder { inner }
+    }
+
+    /// Release the wrapped transmitter.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> MutBlockingTx for SlipEncoder<T>
+    where T: MutBlockingTx
+{
+    type Error = T::Error;
+
+    /// Send a frame holding just the one octet.
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.puts(&[ch]).map_err(|(_, e)| e)
+    }
+
+    /// Send `data` as one SLIP frame. If this returns `Ok(())`, the whole
+    /// frame was sent. Otherwise you get number of octets of `data` sent and
+    /// the error.
+    fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        let data = data.as_ref();
+        self.inner.putc(END).map_err(|e| (0, e))?;
+        for (count, octet) in data.iter().enumerate() {
+            let result = match *octet {
+                END => self.inner.puts(&[ESC, ESC_END]),
+                ESC => self.inner.puts(&[ESC, ESC_ESC]),
+                octet => self.inner.puts(&[octet]),
+            };
+            result.map_err(|(_, e)| (count, e))?;
+        }
+        self.inner.putc(END).map_err(|e| (data.len(), e))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+/// The ways in which receiving through a `SlipDecoder` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SlipError<E> {
+    /// A frame was too long for the decoder's buffer (in which case it was
+    /// discarded), or for the buffer passed to `read_frame` (in which case
+    /// it was kept).
+    BufferOverflow,
+    /// ESC was followed by something other than ESC_END or ESC_ESC. The
+    /// frame was discarded.
+    UnknownEscape(u8),
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Wraps a `MutBlockingRx` and decodes the SLIP frames it receives.
+///
+/// Each frame is decoded into an internal buffer of `N` octets, so `N` must
+/// be big enough for the longest frame. Empty frames are skipped.
+pub struct SlipDecoder<T, const N: usize> {
+    inner: T,
+    buffer: [u8; N],
+    len: usize,
+    pos: usize,
+}
+
+impl<T, const N: usize> SlipDecoder<T, N>
+    where T: MutBlockingRx
+{
+    /// Wrap a receiver.
+    pub fn new(inner: T) -> SlipDecoder<T, N> {
+        SlipDecoder {
+            inner,
+            buffer: [0; N],
+            len: 0,
+            pos: 0,
+        }
+    }
+
+    /// Read the rest of the current frame into `buf`, or the whole of the
+    /// next frame if the current one has been read. Returns the number of
+    /// octets written.
+    pub fn read_frame(&mut self, buf: &mut [u8]) -> Result<usize, SlipError<T::Error>> {
+        while self.pos == self.len {
+            self.load_frame()?;
+        }
+        let frame = &self.buffer[self.pos..self.len];
+        let dest = buf.get_mut(..frame.len()).ok_or(SlipError::BufferOverflow)?;
+        dest.copy_from_slice(frame);
+        self.pos = self.len;
+        Ok(frame.len())
+    }
+
+    /// Release the wrapped receiver. Any part of a frame not yet read is
+    /// lost.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Read and decode the next frame from the port.
+    fn load_frame(&mut self) -> Result<(), SlipError<T::Error>> {
+        self.len = 0;
+        self.pos = 0;
+        let mut count = 0;
+        let mut error = None;
+        loop {
+            let octet = match self.inner.getc().map_err(SlipError::Inner)? {
+                END => break,
+                ESC => {
+                    match self.inner.getc().map_err(SlipError::Inner)? {
+                        ESC_END => END,
+                        ESC_ESC => ESC,
+                        // An END here still ends the frame.
+                        END => {
+                            error = error.or(Some(SlipError::UnknownEscape(END)));
+                            break;
+                        }
+                        other => {
+                            error = error.or(Some(SlipError::UnknownEscape(other)));
+                            continue;
+                        }
+                    }
+                }
+                octet => octet,
+            };
+            match self.buffer.get_mut(count) {
+                Some(space) => *space = octet,
+                None => error = error.or(Some(SlipError::BufferOverflow)),
+            }
+            count += 1;
+        }
+        // Keep going to the end of a bad frame, so we are in sync for the
+        // next one, then report it.
+        if let Some(e) = error {
+            return Err(e);
+        }
+        self.len = count;
+        Ok(())
+    }
+}
+
+impl<T, const N: usize> MutBlockingRx for SlipDecoder<T, N>
+    where T: MutBlockingRx
+{
+    type Error = SlipError<T::Error>;
+
+    /// Returns the next decoded octet. Frame boundaries are skipped over;
+    /// use `read_frame` if you need them.
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        while self.pos == self.len {
+            self.load_frame()?;
+        }
+        let ch = self.buffer[self.pos];
+        self.pos += 1;
+        Ok(ch)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
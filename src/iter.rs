@@ -0,0 +1,91 @@
+//! # Iterators
+//!
+//! Iterators over the octets a non-blocking receiver has waiting, so a port
+//! can be used with `for` loops and iterator adapters.
+//!
+//! ```
+//! # use embedded_serial::MutNonBlockingRx;
+//! # struct Uart(&'static [u8]);
+//! # impl MutNonBlockingRx for Uart {
+//! #     type Error = ();
+//! #     fn getc_try(&mut self) -> Result<Option<u8>, ()> {
+//! #         let ch = self.0.first().cloned();
+//! #         if ch.is_some() { self.0 = &self.0[1..]; }
+//! #         Ok(ch)
+//! #     }
+//! # }
+//! use embedded_serial::iter::RxIter;
+//!
+//! let mut uart = Uart(b"abc");
+//! let mut count = 0;
+//! for octet in RxIter::new(&mut uart) {
+//!     let _ = octet.unwrap();
+//!     count += 1;
+//! }
+//! assert_eq!(count, 3);
+//! ```
+
+use crate::{ImmutNonBlockingRx, MutNonBlockingRx};
+
+/// Iterates over the octets waiting in a `MutNonBlockingRx`.
+///
+/// Each call to `next` calls `getc_try` once. The iterator ends as soon as
+/// nothing is waiting; errors are yielded as `Some(Err(e))`, and iteration
+/// can carry on afterwards. As more data may arrive later, the iterator can
+/// return more octets after it has returned `None`.
+pub struct RxIter<'a, T>
+    where T: 'a + ?Sized
+{
+    inner: &'a mut T,
+}
+
+impl<'a, T> RxIter<'a, T>
+    where T: 'a + ?Sized + MutNonBlockingRx
+{
+    /// Iterate over the octets waiting in `inner`.
+    pub fn new(inner: &'a mut T) -> RxIter<'a, T> {
+        RxIter { inner }
+    }
+}
+
+impl<'a, T> Iterator for RxIter<'a, T>
+    where T: 'a + ?Sized + MutNonBlockingRx
+{
+    type Item = Result<u8, T::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.getc_try().transpose()
+    }
+}
+
+/// As `RxIter`, but for receivers which implement `ImmutNonBlockingRx`.
+pub struct ImmutRxIter<'a, T>
+    where T: 'a + ?Sized
+{
+    inner: &'a T,
+}
+
+impl<'a, T> ImmutRxIter<'a, T>
+    where T: 'a + ?Sized + ImmutNonBlockingRx
+{
+    /// Iterate over the octets waiting in `inner`.
+    pub fn new(inner: &'a T) -> ImmutRxIter<'a, T> {
+        ImmutRxIter { inner }
+    }
+}
+
+impl<'a, T> Iterator for ImmutRxIter<'a, T>
+    where T: 'a + ?Sized + ImmutNonBlockingRx
+{
+    type Item = Result<u8, T::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.getc_try().transpose()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -196,6 +196,41 @@
 
 #![no_std]
 #![deny(missing_docs)]
+// The trait methods have always written `I: ?Sized` inline.
+#![allow(clippy::multiple_bound_locations)]
+
+pub mod adapters;
+pub mod at;
+#[cfg(feature = "async")]
+pub mod asynch;
+pub mod bits;
+pub mod bridge;
+pub mod channel;
+pub mod config;
+pub mod console;
+pub mod delay;
+pub mod dmx;
+pub mod encoded;
+pub mod error;
+pub mod ext;
+pub mod framing;
+pub mod iter;
+pub mod lin;
+pub mod line;
+pub mod modbus;
+pub mod multidrop;
+pub mod nb;
+pub mod nmea;
+pub mod null;
+pub mod one_wire;
+pub mod poll;
+pub mod testing;
+pub mod xmodem;
+
+mod codec;
+mod crc;
+mod macros;
+mod refs;
 
 // Earlier names for the traits, which assume mutability.
 pub use MutBlockingTx as BlockingTx;
@@ -204,6 +239,7 @@ pub use MutNonBlockingTx as NonBlockingTx;
 pub use MutBlockingRx as BlockingRx;
 pub use MutBlockingRxWithTimeout as BlockingRxWithTimeout;
 pub use MutNonBlockingRx as NonBlockingRx;
+pub use MutNonBlockingRxWithTimeout as NonBlockingRxWithTimeout;
 
 /// Implementors of this trait offer octet based serial data transmission
 /// using a blocking API and requiring a mutable reference to self.
@@ -216,7 +252,7 @@ pub trait MutBlockingTx {
     /// (not necessarily that the octet has been transmitted).
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     fn putc(&mut self, ch: u8) -> Result<(), Self::Error>;
 
     /// Write a complete string to the UART.
@@ -230,6 +266,28 @@ pub trait MutBlockingTx {
         }
         Ok(())
     }
+
+    /// Wait until every octet accepted so far has actually been shifted out
+    /// onto the wire, not just stored in the FIFO/buffer. Useful before
+    /// de-asserting an RS-485 driver enable or powering the transmitter down.
+    ///
+    /// The default implementation does nothing, for implementations which
+    /// can't tell when the transmitter is empty.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Write `byte` to the UART `count` times, e.g. for padding or a
+    /// synchronisation sequence. Implementations with a FIFO may be able to
+    /// do this faster than `puts` can.
+    /// If this returns `Ok(())`, all `count` octets were sent.
+    /// Otherwise you get number of octets sent and the error.
+    fn puts_repeat(&mut self, byte: u8, count: usize) -> Result<(), (usize, Self::Error)> {
+        for sent in 0..count {
+            self.putc(byte).map_err(|e| (sent, e))?;
+        }
+        Ok(())
+    }
 }
 
 /// Implementors of this trait offer octet based serial data transmission
@@ -246,7 +304,7 @@ pub trait MutBlockingTxWithTimeout {
     /// octet has been transmitted) or some timeout occurs.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     ///
     /// If it times out, `Ok(None)` is returned.
     /// If it sends the data, `Ok(Some(ch))` is returned.
@@ -277,6 +335,36 @@ pub trait MutBlockingTxWithTimeout {
         }
         Ok(count)
     }
+
+    /// Wait until every octet accepted so far has actually been shifted out
+    /// onto the wire, not just stored in the FIFO/buffer, or a timeout
+    /// occurs.
+    ///
+    /// If the transmitter is empty, `Ok(true)` is returned.
+    /// If it times out, `Ok(false)` is returned.
+    /// If it fails, `Err(...)` is returned.
+    ///
+    /// The default implementation does nothing and returns `Ok(true)`, for
+    /// implementations which can't tell when the transmitter is empty.
+    fn flush_wait(&mut self, _timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+
+    /// Write `byte` to the UART `count` times, as `puts_repeat` does.
+    /// Returns the number of octets written, or an error and the number of
+    /// octets written, as `puts_wait` does.
+    fn puts_repeat_wait(&mut self,
+                        byte: u8,
+                        count: usize,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)> {
+        for sent in 0..count {
+            if self.putc_wait(byte, timeout).map_err(|e| (sent, e))?.is_none() {
+                return Ok(sent);
+            }
+        }
+        Ok(count)
+    }
 }
 
 /// Implementors of this trait offer octet based serial data transmission
@@ -291,7 +379,7 @@ pub trait MutNonBlockingTx {
     /// if it was stored OK.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     fn putc_try(&mut self, ch: u8) -> Result<Option<u8>, Self::Error>;
 
     /// Write as much of a complete string to the UART as possible.
@@ -311,6 +399,28 @@ pub trait MutNonBlockingTx {
         }
         Ok(count)
     }
+
+    /// Wait until every octet accepted so far has actually been shifted out
+    /// onto the wire, not just stored in the FIFO/buffer. Useful before
+    /// de-asserting an RS-485 driver enable or powering the transmitter down.
+    ///
+    /// The default implementation does nothing, for implementations which
+    /// can't tell when the transmitter is empty.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Write `byte` to the UART `count` times, as `puts_repeat` does.
+    /// Returns the number of octets written, or an error and the number of
+    /// octets written, as `puts_try` does.
+    fn puts_repeat_try(&mut self, byte: u8, count: usize) -> Result<usize, (usize, Self::Error)> {
+        for sent in 0..count {
+            if self.putc_try(byte).map_err(|e| (sent, e))?.is_none() {
+                return Ok(sent);
+            }
+        }
+        Ok(count)
+    }
 }
 
 /// Implementors of this trait offer octet based serial data reception
@@ -323,14 +433,14 @@ pub trait MutBlockingRx {
     /// blocking until the octet can be read from the buffer.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     fn getc(&mut self) -> Result<u8, Self::Error>;
 
     /// Read a specified number of octets into the given buffer, blocking
     /// until that many have been read.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     fn gets<I: ?Sized>(&mut self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
         where I: AsMut<[u8]>
     {
@@ -339,6 +449,15 @@ pub trait MutBlockingRx {
         }
         Ok(())
     }
+
+    /// Returns how many octets are waiting in the receive FIFO/buffer, which
+    /// can be read without blocking.
+    ///
+    /// The default implementation returns `Ok(0)`, which means the number
+    /// is unknown - not that nothing is waiting.
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
 }
 
 /// Implementors of this trait offer octet based serial data reception using a
@@ -354,18 +473,26 @@ pub trait MutBlockingRxWithTimeout {
     /// blocking until the octet can be read from the buffer.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     ///
     /// If it times out, Ok(None) is returned.
     /// If it receives data, Ok(Some(data)) is returned.
     /// If it fails, Err(...) is returned.
     fn getc_wait(&mut self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error>;
 
+    /// Wait for the first octet of some data to arrive, and read it. This is
+    /// exactly `getc_wait`, but makes it clear at the call site that you are
+    /// waiting for something to start arriving rather than reading the next
+    /// octet of a message.
+    fn poll_until_ready(&mut self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        self.getc_wait(timeout)
+    }
+
     /// Read a specified number of octets into the given buffer, blocking
     /// until that many have been read or a timeout occurs.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     ///
     /// If the result is `Ok(size)` but `size <= buffer.len()`, you had a timeout.
     fn gets_wait<I: ?Sized>(&mut self,
@@ -385,6 +512,28 @@ pub trait MutBlockingRxWithTimeout {
         }
         Ok(count)
     }
+
+    /// Returns how many octets are waiting in the receive FIFO/buffer, which
+    /// can be read without blocking.
+    ///
+    /// The default implementation returns `Ok(0)`, which means the number
+    /// is unknown - not that nothing is waiting.
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+}
+
+/// Implementors of this trait can wait for data to arrive at their
+/// receiver without reading it. Only implement this if the port can really
+/// wait - reading an octet ahead and holding it back doesn't count.
+pub trait RxReady: MutBlockingRxWithTimeout {
+    /// Wait until data starts arriving at the port's receiver, or a timeout
+    /// occurs. Any data is left in the receiver to be read.
+    ///
+    /// If some data is waiting, `Ok(true)` is returned.
+    /// If it times out, `Ok(false)` is returned.
+    /// If it fails, `Err(...)` is returned.
+    fn wait_for_rx_ready(&mut self, timeout: &Self::Timeout) -> Result<bool, Self::Error>;
 }
 
 /// Implementors of this trait offer octet based serial data reception using a
@@ -397,7 +546,7 @@ pub trait MutNonBlockingRx {
     /// is empty return None.
     ///
     /// In some implementations, this can result in an Error. If not, use
-    /// `type Error = !`.
+    /// `type Error = error::NoError`.
     ///
     /// If it times out, Ok(None) is returned.
     /// If it receives data, Ok(Some(data)) is returned.
@@ -408,7 +557,7 @@ pub trait MutNonBlockingRx {
     /// data runs out.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     ///
     /// If the result is `Ok(size)` but `size <= buffer.len()`, you ran out of data.
     fn gets_try<I: ?Sized>(&mut self, buffer: &mut I) -> Result<usize, (usize, Self::Error)>
@@ -425,6 +574,97 @@ pub trait MutNonBlockingRx {
         }
         Ok(count)
     }
+
+    /// Continue filling a partially filled buffer. The first `offset` octets
+    /// of `buffer` are left alone and new octets are read into the rest, as
+    /// with `gets_try(&mut buffer[offset..])`.
+    ///
+    /// The result (in both the `Ok` and `Err` case) is the total number of
+    /// valid octets now in `buffer`, including the `offset` octets which were
+    /// already there. Panics if `offset > buffer.len()`.
+    fn gets_remainder(&mut self,
+                      buffer: &mut [u8],
+                      offset: usize)
+                      -> Result<usize, (usize, Self::Error)> {
+        match self.gets_try(&mut buffer[offset..]) {
+            Ok(count) => Ok(offset + count),
+            Err((count, e)) => Err((offset + count, e)),
+        }
+    }
+
+    /// Read whatever data is waiting, up to the size of `buffer`. This is
+    /// exactly `gets_try`, under a name which says what it does at the call
+    /// site.
+    fn gets_drain(&mut self, buffer: &mut [u8]) -> Result<usize, (usize, Self::Error)> {
+        self.gets_try(buffer)
+    }
+
+    /// Returns how many octets are waiting in the receive FIFO/buffer, which
+    /// can be read without blocking.
+    ///
+    /// The default implementation returns `Ok(0)`, which means the number
+    /// is unknown - not that nothing is waiting.
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+}
+
+/// Implementors of this trait offer octet based serial data reception using a
+/// non-blocking API with an upper bound on how long to keep trying, and
+/// requiring a mutable reference to self.
+///
+/// Unlike `MutBlockingRxWithTimeout`, an implementation may spin on the
+/// FIFO status until the deadline rather than sleeping.
+pub trait MutNonBlockingRxWithTimeout {
+    /// The type used to specify the timeout.
+    type Timeout;
+    /// The error type returned if `getc_try_wait` fails.
+    type Error;
+
+    /// Keep checking the port's receiver for an octet until one arrives or
+    /// the timeout expires.
+    ///
+    /// In some implementations, this can result in an Error.
+    /// If not, use `type Error = error::NoError`.
+    ///
+    /// If it times out, `Ok(None)` is returned.
+    /// If it receives data, `Ok(Some(data))` is returned.
+    /// If it fails, `Err(...)` is returned.
+    fn getc_try_wait(&mut self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error>;
+
+    /// Read a specified number of octets into the given buffer, giving up
+    /// if any one of them doesn't arrive before the timeout.
+    ///
+    /// In some implementations, this can result in an Error.
+    /// If not, use `type Error = error::NoError`.
+    ///
+    /// If the result is `Ok(size)` but `size < buffer.len()`, you had a timeout.
+    fn gets_try_wait<I: ?Sized>(&mut self,
+                                buffer: &mut I,
+                                timeout: &Self::Timeout)
+                                -> Result<usize, (usize, Self::Error)>
+        where I: AsMut<[u8]>
+    {
+        let mut count: usize = 0;
+        for space in buffer.as_mut() {
+            *space = match self.getc_try_wait(timeout) {
+                Err(e) => return Err((count, e)),
+                Ok(None) => return Ok(count),
+                Ok(Some(ch)) => ch,
+            };
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Returns how many octets are waiting in the receive FIFO/buffer, which
+    /// can be read without blocking.
+    ///
+    /// The default implementation returns `Ok(0)`, which means the number
+    /// is unknown - not that nothing is waiting.
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
 }
 
 /// Implementors of this trait offer octet based serial data transmission
@@ -438,7 +678,7 @@ pub trait ImmutBlockingTx {
     /// (not necessarily that the octet has been transmitted).
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     fn putc(&self, ch: u8) -> Result<(), Self::Error>;
 
     /// Write a complete string to the UART.
@@ -452,6 +692,28 @@ pub trait ImmutBlockingTx {
         }
         Ok(())
     }
+
+    /// Wait until every octet accepted so far has actually been shifted out
+    /// onto the wire, not just stored in the FIFO/buffer. Useful before
+    /// de-asserting an RS-485 driver enable or powering the transmitter down.
+    ///
+    /// The default implementation does nothing, for implementations which
+    /// can't tell when the transmitter is empty.
+    fn flush(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Write `byte` to the UART `count` times, e.g. for padding or a
+    /// synchronisation sequence. Implementations with a FIFO may be able to
+    /// do this faster than `puts` can.
+    /// If this returns `Ok(())`, all `count` octets were sent.
+    /// Otherwise you get number of octets sent and the error.
+    fn puts_repeat(&self, byte: u8, count: usize) -> Result<(), (usize, Self::Error)> {
+        for sent in 0..count {
+            self.putc(byte).map_err(|e| (sent, e))?;
+        }
+        Ok(())
+    }
 }
 
 /// Implementors of this trait offer octet based serial data transmission
@@ -468,7 +730,7 @@ pub trait ImmutBlockingTxWithTimeout {
     /// octet has been transmitted) or some timeout occurs.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     ///
     /// If it times out, `Ok(None)` is returned.
     /// If it sends the data, `Ok(Some(ch))` is returned.
@@ -499,6 +761,36 @@ pub trait ImmutBlockingTxWithTimeout {
         }
         Ok(count)
     }
+
+    /// Wait until every octet accepted so far has actually been shifted out
+    /// onto the wire, not just stored in the FIFO/buffer, or a timeout
+    /// occurs.
+    ///
+    /// If the transmitter is empty, `Ok(true)` is returned.
+    /// If it times out, `Ok(false)` is returned.
+    /// If it fails, `Err(...)` is returned.
+    ///
+    /// The default implementation does nothing and returns `Ok(true)`, for
+    /// implementations which can't tell when the transmitter is empty.
+    fn flush_wait(&self, _timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+
+    /// Write `byte` to the UART `count` times, as `puts_repeat` does.
+    /// Returns the number of octets written, or an error and the number of
+    /// octets written, as `puts_wait` does.
+    fn puts_repeat_wait(&self,
+                        byte: u8,
+                        count: usize,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)> {
+        for sent in 0..count {
+            if self.putc_wait(byte, timeout).map_err(|e| (sent, e))?.is_none() {
+                return Ok(sent);
+            }
+        }
+        Ok(count)
+    }
 }
 
 /// Implementors of this trait offer octet based serial data transmission
@@ -513,7 +805,7 @@ pub trait ImmutNonBlockingTx {
     /// if it was stored OK.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     fn putc_try(&self, ch: u8) -> Result<Option<u8>, Self::Error>;
 
     /// Write as much of a complete string to the UART as possible.
@@ -533,6 +825,107 @@ pub trait ImmutNonBlockingTx {
         }
         Ok(count)
     }
+
+    /// Wait until every octet accepted so far has actually been shifted out
+    /// onto the wire, not just stored in the FIFO/buffer. Useful before
+    /// de-asserting an RS-485 driver enable or powering the transmitter down.
+    ///
+    /// The default implementation does nothing, for implementations which
+    /// can't tell when the transmitter is empty.
+    fn flush(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Write `byte` to the UART `count` times, as `puts_repeat` does.
+    /// Returns the number of octets written, or an error and the number of
+    /// octets written, as `puts_try` does.
+    fn puts_repeat_try(&self, byte: u8, count: usize) -> Result<usize, (usize, Self::Error)> {
+        for sent in 0..count {
+            if self.putc_try(byte).map_err(|e| (sent, e))?.is_none() {
+                return Ok(sent);
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// Implementors of this trait offer octet based serial data transmission
+/// using a non-blocking API with an upper bound on how long to keep trying,
+/// and only requiring an immutable reference to self.
+///
+/// Unlike `ImmutBlockingTxWithTimeout`, an implementation may spin on the
+/// FIFO status until the deadline rather than sleeping.
+pub trait ImmutNonBlockingTxWithTimeout {
+    /// The type used to specify the timeout.
+    type Timeout;
+    /// The error type returned if a function fails.
+    type Error;
+
+    /// Keep trying to write a single octet to the port's transmitter until
+    /// it can be stored in the FIFO/buffer or the timeout expires.
+    ///
+    /// In some implementations, this can result in an Error.
+    /// If not, use `type Error = error::NoError`.
+    ///
+    /// If it times out, `Ok(None)` is returned.
+    /// If it stores the octet, `Ok(Some(ch))` is returned.
+    /// If it fails, `Err(...)` is returned.
+    fn putc_try_wait(&self, ch: u8, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error>;
+
+    /// Attempts to write a complete string to the UART.
+    /// Returns number of octets written, or an error and the number of octets written.
+    /// The timeout applies to each octet individually.
+    ///
+    /// A result of `Ok(data.len())` means all the data was sent.
+    /// A result of `Ok(size < data.len())` means only some of the data was sent then there was a timeout.
+    /// A result of `Err(size, e)` means some (or all) of the data was sent then there was an error.
+    fn puts_try_wait<I: ?Sized>(&self,
+                                data: &I,
+                                timeout: &Self::Timeout)
+                                -> Result<usize, (usize, Self::Error)>
+        where I: AsRef<[u8]>
+    {
+        let mut count: usize = 0;
+        for octet in data.as_ref() {
+            // If we get an error, return it (with the number of bytes sent),
+            // else if we get None, we timed out so abort.
+            if self.putc_try_wait(*octet, timeout).map_err(|e| (count, e))?.is_none() {
+                break;
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Wait until every octet accepted so far has actually been shifted out
+    /// onto the wire, not just stored in the FIFO/buffer, or a timeout
+    /// occurs.
+    ///
+    /// If the transmitter is empty, `Ok(true)` is returned.
+    /// If it times out, `Ok(false)` is returned.
+    /// If it fails, `Err(...)` is returned.
+    ///
+    /// The default implementation does nothing and returns `Ok(true)`, for
+    /// implementations which can't tell when the transmitter is empty.
+    fn flush_wait(&self, _timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+
+    /// Write `byte` to the UART `count` times, as `puts_repeat` does.
+    /// Returns the number of octets written, or an error and the number of
+    /// octets written, as `puts_try_wait` does.
+    fn puts_repeat_try_wait(&self,
+                            byte: u8,
+                            count: usize,
+                            timeout: &Self::Timeout)
+                            -> Result<usize, (usize, Self::Error)> {
+        for sent in 0..count {
+            if self.putc_try_wait(byte, timeout).map_err(|e| (sent, e))?.is_none() {
+                return Ok(sent);
+            }
+        }
+        Ok(count)
+    }
 }
 
 /// Implementors of this trait offer octet based serial data reception
@@ -545,14 +938,14 @@ pub trait ImmutBlockingRx {
     /// blocking until the octet can be read from the buffer.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     fn getc(&self) -> Result<u8, Self::Error>;
 
     /// Read a specified number of octets into the given buffer, blocking
     /// until that many have been read.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     fn gets<I: ?Sized>(&self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
         where I: AsMut<[u8]>
     {
@@ -561,6 +954,15 @@ pub trait ImmutBlockingRx {
         }
         Ok(())
     }
+
+    /// Returns how many octets are waiting in the receive FIFO/buffer, which
+    /// can be read without blocking.
+    ///
+    /// The default implementation returns `Ok(0)`, which means the number
+    /// is unknown - not that nothing is waiting.
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
 }
 
 /// Implementors of this trait offer octet based serial data reception using a
@@ -576,7 +978,7 @@ pub trait ImmutBlockingRxWithTimeout {
     /// blocking until the octet can be read from the buffer.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     ///
     /// If it times out, Ok(None) is returned.
     /// If it receives data, Ok(Some(data)) is returned.
@@ -587,7 +989,7 @@ pub trait ImmutBlockingRxWithTimeout {
     /// until that many have been read or a timeout occurs.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     ///
     /// If the result is `Ok(size)` but `size <= buffer.len()`, you had a timeout.
     fn gets_wait<I: ?Sized>(&self,
@@ -607,6 +1009,15 @@ pub trait ImmutBlockingRxWithTimeout {
         }
         Ok(count)
     }
+
+    /// Returns how many octets are waiting in the receive FIFO/buffer, which
+    /// can be read without blocking.
+    ///
+    /// The default implementation returns `Ok(0)`, which means the number
+    /// is unknown - not that nothing is waiting.
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
 }
 
 /// Implementors of this trait offer octet based serial data reception using a
@@ -619,7 +1030,7 @@ pub trait ImmutNonBlockingRx {
     /// is empty return None.
     ///
     /// In some implementations, this can result in an Error. If not, use
-    /// `type Error = !`.
+    /// `type Error = error::NoError`.
     ///
     /// If it times out, Ok(None) is returned.
     /// If it receives data, Ok(Some(data)) is returned.
@@ -630,7 +1041,7 @@ pub trait ImmutNonBlockingRx {
     /// data runs out.
     ///
     /// In some implementations, this can result in an Error.
-    /// If not, use `type Error = !`.
+    /// If not, use `type Error = error::NoError`.
     ///
     /// If the result is `Ok(size)` but `size <= buffer.len()`, you ran out of data.
     fn gets_try<I: ?Sized>(&self, buffer: &mut I) -> Result<usize, (usize, Self::Error)>
@@ -647,6 +1058,246 @@ pub trait ImmutNonBlockingRx {
         }
         Ok(count)
     }
+
+    /// Continue filling a partially filled buffer. The first `offset` octets
+    /// of `buffer` are left alone and new octets are read into the rest, as
+    /// with `gets_try(&mut buffer[offset..])`.
+    ///
+    /// The result (in both the `Ok` and `Err` case) is the total number of
+    /// valid octets now in `buffer`, including the `offset` octets which were
+    /// already there. Panics if `offset > buffer.len()`.
+    fn gets_remainder(&self,
+                      buffer: &mut [u8],
+                      offset: usize)
+                      -> Result<usize, (usize, Self::Error)> {
+        match self.gets_try(&mut buffer[offset..]) {
+            Ok(count) => Ok(offset + count),
+            Err((count, e)) => Err((offset + count, e)),
+        }
+    }
+
+    /// Read whatever data is waiting, up to the size of `buffer`. This is
+    /// exactly `gets_try`, under a name which says what it does at the call
+    /// site.
+    fn gets_drain(&self, buffer: &mut [u8]) -> Result<usize, (usize, Self::Error)> {
+        self.gets_try(buffer)
+    }
+
+    /// Returns how many octets are waiting in the receive FIFO/buffer, which
+    /// can be read without blocking.
+    ///
+    /// The default implementation returns `Ok(0)`, which means the number
+    /// is unknown - not that nothing is waiting.
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+}
+
+/// Implementors of this trait offer octet based serial data reception using a
+/// non-blocking API with an upper bound on how long to keep trying, and
+/// only requiring an immutable reference to self.
+///
+/// Unlike `ImmutBlockingRxWithTimeout`, an implementation may spin on the
+/// FIFO status until the deadline rather than sleeping.
+pub trait ImmutNonBlockingRxWithTimeout {
+    /// The type used to specify the timeout.
+    type Timeout;
+    /// The error type returned if `getc_try_wait` fails.
+    type Error;
+
+    /// Keep checking the port's receiver for an octet until one arrives or
+    /// the timeout expires.
+    ///
+    /// In some implementations, this can result in an Error.
+    /// If not, use `type Error = error::NoError`.
+    ///
+    /// If it times out, `Ok(None)` is returned.
+    /// If it receives data, `Ok(Some(data))` is returned.
+    /// If it fails, `Err(...)` is returned.
+    fn getc_try_wait(&self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error>;
+
+    /// Read a specified number of octets into the given buffer, giving up
+    /// if any one of them doesn't arrive before the timeout.
+    ///
+    /// In some implementations, this can result in an Error.
+    /// If not, use `type Error = error::NoError`.
+    ///
+    /// If the result is `Ok(size)` but `size < buffer.len()`, you had a timeout.
+    fn gets_try_wait<I: ?Sized>(&self,
+                                buffer: &mut I,
+                                timeout: &Self::Timeout)
+                                -> Result<usize, (usize, Self::Error)>
+        where I: AsMut<[u8]>
+    {
+        let mut count: usize = 0;
+        for space in buffer.as_mut() {
+            *space = match self.getc_try_wait(timeout) {
+                Err(e) => return Err((count, e)),
+                Ok(None) => return Ok(count),
+                Ok(Some(ch)) => ch,
+            };
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Returns how many octets are waiting in the receive FIFO/buffer, which
+    /// can be read without blocking.
+    ///
+    /// The default implementation returns `Ok(0)`, which means the number
+    /// is unknown - not that nothing is waiting.
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+}
+
+// Combined Tx and Rx traits, for ports used in both directions.
+
+/// A port which offers both `MutBlockingTx` and `MutBlockingRx`, with the
+/// same error type for both.
+///
+/// This is implemented automatically for any suitable type. Refer to the
+/// error as `<T as MutBlockingTx>::Error`.
+pub trait MutBlockingSerial: MutBlockingTx
+    + MutBlockingRx<Error = <Self as MutBlockingTx>::Error>
+{
+}
+
+impl<T> MutBlockingSerial for T
+    where T: ?Sized + MutBlockingTx
+          + MutBlockingRx<Error = <T as MutBlockingTx>::Error>
+{
+}
+
+/// A port which offers both `MutBlockingTxWithTimeout` and
+/// `MutBlockingRxWithTimeout`, with the same error and timeout types for
+/// both.
+///
+/// This is implemented automatically for any suitable type. Refer to the
+/// error as `<T as MutBlockingTxWithTimeout>::Error`.
+pub trait MutBlockingSerialWithTimeout: MutBlockingTxWithTimeout
+    + MutBlockingRxWithTimeout<Timeout = <Self as MutBlockingTxWithTimeout>::Timeout,
+                               Error = <Self as MutBlockingTxWithTimeout>::Error>
+{
+}
+
+impl<T> MutBlockingSerialWithTimeout for T
+    where T: ?Sized + MutBlockingTxWithTimeout
+          + MutBlockingRxWithTimeout<Timeout = <T as MutBlockingTxWithTimeout>::Timeout,
+                                     Error = <T as MutBlockingTxWithTimeout>::Error>
+{
+}
+
+/// A port which offers both `MutNonBlockingTx` and `MutNonBlockingRx`, with
+/// the same error type for both.
+///
+/// This is implemented automatically for any suitable type. Refer to the
+/// error as `<T as MutNonBlockingTx>::Error`.
+pub trait MutNonBlockingSerial: MutNonBlockingTx
+    + MutNonBlockingRx<Error = <Self as MutNonBlockingTx>::Error>
+{
+}
+
+impl<T> MutNonBlockingSerial for T
+    where T: ?Sized + MutNonBlockingTx
+          + MutNonBlockingRx<Error = <T as MutNonBlockingTx>::Error>
+{
+}
+
+/// A port which offers both `ImmutBlockingTx` and `ImmutBlockingRx`, with the
+/// same error type for both.
+///
+/// This is implemented automatically for any suitable type. Refer to the
+/// error as `<T as ImmutBlockingTx>::Error`.
+pub trait ImmutBlockingSerial: ImmutBlockingTx
+    + ImmutBlockingRx<Error = <Self as ImmutBlockingTx>::Error>
+{
+}
+
+impl<T> ImmutBlockingSerial for T
+    where T: ?Sized + ImmutBlockingTx
+          + ImmutBlockingRx<Error = <T as ImmutBlockingTx>::Error>
+{
+}
+
+/// A port which offers both `ImmutBlockingTxWithTimeout` and
+/// `ImmutBlockingRxWithTimeout`, with the same error and timeout types for
+/// both.
+///
+/// This is implemented automatically for any suitable type. Refer to the
+/// error as `<T as ImmutBlockingTxWithTimeout>::Error`.
+pub trait ImmutBlockingSerialWithTimeout: ImmutBlockingTxWithTimeout
+    + ImmutBlockingRxWithTimeout<Timeout = <Self as ImmutBlockingTxWithTimeout>::Timeout,
+                                 Error = <Self as ImmutBlockingTxWithTimeout>::Error>
+{
+}
+
+impl<T> ImmutBlockingSerialWithTimeout for T
+    where T: ?Sized + ImmutBlockingTxWithTimeout
+          + ImmutBlockingRxWithTimeout<Timeout = <T as ImmutBlockingTxWithTimeout>::Timeout,
+                                       Error = <T as ImmutBlockingTxWithTimeout>::Error>
+{
+}
+
+/// A port which offers both `ImmutNonBlockingTx` and `ImmutNonBlockingRx`,
+/// with the same error type for both.
+///
+/// This is implemented automatically for any suitable type. Refer to the
+/// error as `<T as ImmutNonBlockingTx>::Error`.
+pub trait ImmutNonBlockingSerial: ImmutNonBlockingTx
+    + ImmutNonBlockingRx<Error = <Self as ImmutNonBlockingTx>::Error>
+{
+}
+
+impl<T> ImmutNonBlockingSerial for T
+    where T: ?Sized + ImmutNonBlockingTx
+          + ImmutNonBlockingRx<Error = <T as ImmutNonBlockingTx>::Error>
+{
+}
+
+/// A port which offers both `ImmutNonBlockingTxWithTimeout` and
+/// `ImmutNonBlockingRxWithTimeout`, with the same error and timeout types for
+/// both.
+///
+/// This is implemented automatically for any suitable type. Refer to the
+/// error as `<T as ImmutNonBlockingTxWithTimeout>::Error`.
+pub trait ImmutNonBlockingSerialWithTimeout: ImmutNonBlockingTxWithTimeout
+    + ImmutNonBlockingRxWithTimeout<Timeout = <Self as ImmutNonBlockingTxWithTimeout>::Timeout,
+                                    Error = <Self as ImmutNonBlockingTxWithTimeout>::Error>
+{
+}
+
+impl<T> ImmutNonBlockingSerialWithTimeout for T
+    where T: ?Sized + ImmutNonBlockingTxWithTimeout
+          + ImmutNonBlockingRxWithTimeout<Timeout = <T as ImmutNonBlockingTxWithTimeout>::Timeout,
+                                          Error = <T as ImmutNonBlockingTxWithTimeout>::Error>
+{
+}
+
+// Splitting a port into independent halves.
+
+/// Implementors of this trait can be split into a transmit half and a
+/// receive half, which can then be handed to different tasks (or
+/// interrupt handlers) and used independently.
+///
+/// How the halves share the peripheral is up to the implementation.
+pub trait SplitableSerial {
+    /// The error type returned if a function on either half fails.
+    type Error;
+    /// The transmit half.
+    type TxHalf: MutBlockingTx<Error = Self::Error>;
+    /// The receive half.
+    type RxHalf: MutBlockingRx<Error = Self::Error>;
+
+    /// Split the port into its two halves.
+    fn split(self) -> (Self::TxHalf, Self::RxHalf);
+}
+
+/// Implementors of this trait can be put back together from the two halves
+/// returned by `SplitableSerial::split`.
+pub trait UnsplitableSerial: SplitableSerial + Sized {
+    /// Recombine the two halves into the original port.
+    fn unsplit(tx: Self::TxHalf, rx: Self::RxHalf) -> Self;
 }
 
 // ****************************************************************************
@@ -0,0 +1,226 @@
+//! # LIN bus
+//!
+//! Every LIN frame starts with a header sent by the master node: a break
+//! (at least 13 bit-times of dominant level), the sync octet 0x55, and the
+//! protected identifier, which is a six-bit frame ID plus two parity bits.
+//! The response which follows (from the master or a slave) is up to eight
+//! data octets and a checksum.
+
+use crate::line::BreakTx;
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// The length of the break which starts a LIN header, in bit-times.
+pub const LIN_BREAK_BITS: u16 = 13;
+
+/// The sync octet which follows the break in a LIN header.
+pub const LIN_SYNC: u8 = 0x55;
+
+/// The largest frame ID which fits in a LIN protected identifier.
+pub const LIN_MAX_ID: u8 = 0x3F;
+
+/// The most data octets a LIN response can carry.
+pub const LIN_MAX_DATA: usize = 8;
+
+/// The ways in which a LIN transfer can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LinError<E> {
+    /// The frame ID doesn't fit in six bits.
+    InvalidId(u8),
+    /// A response was sent or received without a header being sent first.
+    NoHeader,
+    /// The response length was zero, more than `LIN_MAX_DATA`, or more than
+    /// the buffer provided.
+    InvalidLength(usize),
+    /// The checksum received didn't match the response.
+    ChecksumMismatch {
+        /// The checksum calculated over the received response.
+        expected: u8,
+        /// The checksum which was received.
+        got: u8,
+    },
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Implementors of this trait can act as the master node on a LIN bus. It is
+/// implemented for every port which implements `BreakTx`.
+pub trait LinSerial: MutBlockingTx + BreakTx {
+    /// Send a LIN header for frame `pid`: a break of `LIN_BREAK_BITS`
+    /// bit-times, the sync octet, then `pid` with its parity bits (see
+    /// `lin_parity`) in the top two bits.
+    ///
+    /// `pid` is the six-bit frame ID, so it must be no more than
+    /// `LIN_MAX_ID`.
+    fn send_lin_header(&mut self, pid: u8) -> Result<(), LinError<Self::Error>> {
+        if pid > LIN_MAX_ID {
+            return Err(LinError::InvalidId(pid));
+        }
+        self.send_break(LIN_BREAK_BITS).map_err(LinError::Inner)?;
+        self.putc(LIN_SYNC).map_err(LinError::Inner)?;
+        self.putc(pid | lin_parity(pid)).map_err(LinError::Inner)
+    }
+}
+
+impl<T> LinSerial for T where T: ?Sized + BreakTx {}
+
+/// Which checksum protects a LIN response.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LinChecksum {
+    /// The LIN 1.x checksum, over the data octets only.
+    Classic,
+    /// The LIN 2.x checksum, over the protected identifier and the data.
+    /// The diagnostic and reserved frames (IDs 0x3C to 0x3F) still use the
+    /// classic checksum.
+    Enhanced,
+}
+
+/// The gaps `LinBusMaster` waits for when it sends a response.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LinDelay {
+    /// The gap between the header and the first octet of the response.
+    ResponseSpace,
+    /// The gap between two octets of a response.
+    InterByte,
+}
+
+/// Drives a LIN bus as the master node.
+///
+/// `delay` is called before the master sends a response, and between its
+/// octets, to give the response space and inter-byte space the bus's
+/// schedule needs, and is told which of the two it is waiting for.
+///
+/// LIN transceivers usually echo what is sent back to the receiver; the
+/// port must discard the echo, so that only octets from other nodes are
+/// read.
+pub struct LinBusMaster<T, F = fn(LinDelay)> {
+    inner: T,
+    checksum: LinChecksum,
+    delay: F,
+    pid: Option<u8>,
+}
+
+impl<T> LinBusMaster<T>
+    where T: BreakTx + MutBlockingRx<Error = <T as MutBlockingTx>::Error>
+{
+    /// Wrap a port, without any delays.
+    pub fn new(inner: T, checksum: LinChecksum) -> LinBusMaster<T> {
+        LinBusMaster::with_delay(inner, checksum, no_delay)
+    }
+}
+
+impl<T, F> LinBusMaster<T, F>
+    where T: BreakTx + MutBlockingRx<Error = <T as MutBlockingTx>::Error>,
+          F: Fn(LinDelay)
+{
+    /// Wrap a port, calling `delay` before and between the octets of each
+    /// response the master sends.
+    pub fn with_delay(inner: T, checksum: LinChecksum, delay: F) -> LinBusMaster<T, F> {
+        LinBusMaster {
+            inner,
+            checksum,
+            delay,
+            pid: None,
+        }
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Send the header for frame `id`, as `LinSerial::send_lin_header`
+    /// does. The following response is checksummed for this frame.
+    pub fn send_header(&mut self, id: u8) -> Result<(), LinError<<T as MutBlockingTx>::Error>> {
+        self.pid = None;
+        self.inner.send_lin_header(id)?;
+        self.pid = Some(id | lin_parity(id));
+        Ok(())
+    }
+
+    /// Send `data` and its checksum as the response to the last header, for
+    /// a frame which the master publishes.
+    pub fn send_response(&mut self,
+                         data: &[u8])
+                         -> Result<(), LinError<<T as MutBlockingTx>::Error>> {
+        let pid = self.check_response(data.len())?;
+        let checksum = self.checksum(pid, data);
+        for (idx, octet) in data.iter().chain(core::iter::once(&checksum)).enumerate() {
+            (self.delay)(if idx == 0 {
+                LinDelay::ResponseSpace
+            } else {
+                LinDelay::InterByte
+            });
+            self.inner.putc(*octet).map_err(LinError::Inner)?;
+        }
+        Ok(())
+    }
+
+    /// Read a response of `expected_len` data octets, and its checksum, into
+    /// `buf`, blocking until it has arrived.
+    pub fn recv_response(&mut self,
+                         expected_len: usize,
+                         buf: &mut [u8])
+                         -> Result<(), LinError<<T as MutBlockingTx>::Error>> {
+        let pid = self.check_response(expected_len)?;
+        let data = buf.get_mut(..expected_len).ok_or(LinError::InvalidLength(expected_len))?;
+        self.inner.gets(data).map_err(|(_, e)| LinError::Inner(e))?;
+        let got = self.inner.getc().map_err(LinError::Inner)?;
+        let expected = self.checksum(pid, data);
+        if got != expected {
+            return Err(LinError::ChecksumMismatch { expected, got });
+        }
+        Ok(())
+    }
+
+    /// Check a header has been sent and `len` is a valid response length,
+    /// and return the header's protected identifier.
+    fn check_response(&self, len: usize) -> Result<u8, LinError<<T as MutBlockingTx>::Error>> {
+        let pid = self.pid.ok_or(LinError::NoHeader)?;
+        if len == 0 || len > LIN_MAX_DATA {
+            return Err(LinError::InvalidLength(len));
+        }
+        Ok(pid)
+    }
+
+    /// The checksum of a response carrying `data`, for the frame `pid`.
+    fn checksum(&self, pid: u8, data: &[u8]) -> u8 {
+        // Frames 0x3C to 0x3F always use the classic checksum.
+        let reserved = pid & LIN_MAX_ID >= 0x3C;
+        let start = match self.checksum {
+            LinChecksum::Enhanced if !reserved => u16::from(pid),
+            _ => 0,
+        };
+        let sum = data.iter().fold(start, |sum, octet| {
+            let sum = sum + u16::from(*octet);
+            if sum > 0xFF { sum - 0xFF } else { sum }
+        });
+        !(sum as u8)
+    }
+}
+
+/// The default delay between octets - none at all.
+fn no_delay(_gap: LinDelay) {}
+
+/// Compute the two parity bits of a LIN protected identifier for the
+/// six-bit frame ID `id`. P0 is returned in bit 6 and P1 in bit 7, so the
+/// protected identifier is `id | lin_parity(id)`. Bits 6 and 7 of `id` are
+/// ignored.
+///
+/// ```
+/// use embedded_serial::lin::lin_parity;
+/// assert_eq!(lin_parity(0x00), 0x80);
+/// assert_eq!(0x3C | lin_parity(0x3C), 0x3C);
+/// assert_eq!(0x01 | lin_parity(0x01), 0xC1);
+/// ```
+pub fn lin_parity(id: u8) -> u8 {
+    let bit = |n: u8| (id >> n) & 1;
+    let p0 = bit(0) ^ bit(1) ^ bit(2) ^ bit(4);
+    let p1 = !(bit(1) ^ bit(3) ^ bit(4) ^ bit(5)) & 1;
+    (p0 << 6) | (p1 << 7)
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,92 @@
+//! # Line conditions
+//!
+//! A break holds the line in the spacing (low) state for longer than a
+//! whole character frame, so it can't be mistaken for data. Protocols use it
+//! to mark the start of a frame (LIN, DMX512), to wake a sleeping node, for
+//! autobaud detection, or to reset a target.
+//!
+//! An inter-character timeout is the opposite: the line is left idle for
+//! longer than some number of character times, which protocols such as
+//! Modbus RTU use to mark the end of a frame.
+
+use core::time::Duration;
+
+use crate::MutBlockingTx;
+
+/// Implementors of this trait can send a break.
+pub trait BreakTx: MutBlockingTx {
+    /// Hold the line in the break condition for `duration_bits` bit-times at
+    /// the current baud rate, blocking until the break has finished.
+    /// Hardware which can't time the break exactly should err on the long
+    /// side.
+    fn send_break(&mut self, duration_bits: u16) -> Result<(), Self::Error>;
+}
+
+/// What is known about a break which was received. Hardware which can't
+/// measure something reports `None` for it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BreakInfo {
+    /// How long the line was held in the break condition.
+    pub duration: Option<Duration>,
+    /// How long one character frame lasts, as estimated from the break (or
+    /// from the characters which followed it), for autobaud detection.
+    pub frame_length_estimate: Option<Duration>,
+}
+
+/// Implementors of this trait can detect a received break.
+pub trait BreakRx {
+    /// Take the most recent break seen, if one has been seen since the last
+    /// call. Each break is only reported once.
+    fn last_break_seen(&mut self) -> Option<BreakInfo>;
+}
+
+/// The ways in which `InterCharacterTimeoutRx::getc_ict` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ICTTimeoutError<E> {
+    /// The line has been idle for the inter-character timeout since the last
+    /// octet was received.
+    Timeout,
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Implementors of this trait report an inter-character timeout (set with
+/// `config::InterCharacterTimeout`) while receiving.
+pub trait InterCharacterTimeoutRx {
+    /// The error type returned if a function fails.
+    type Error;
+
+    /// Read a single octet, blocking until one arrives or the line goes
+    /// idle after the last octet received. Each gap is only reported once;
+    /// after that this blocks until the next octet.
+    fn getc_ict(&mut self) -> Result<u8, ICTTimeoutError<Self::Error>>;
+
+    /// Read one frame into `buf`, treating the inter-character timeout as
+    /// the end of the frame. A gap reported before any octets have been
+    /// read is ignored.
+    ///
+    /// `Ok(n)` gives the length of the frame, or `buf.len()` if the buffer
+    /// filled first; the rest of the frame is then left to be read.
+    /// `Err((n, e))` means `n` octets were stored then there was an error.
+    fn gets_frame_ict(&mut self, buf: &mut [u8]) -> Result<usize, (usize, Self::Error)> {
+        let mut count = 0;
+        while count < buf.len() {
+            match self.getc_ict() {
+                Ok(ch) => {
+                    buf[count] = ch;
+                    count += 1;
+                }
+                Err(ICTTimeoutError::Timeout) if count == 0 => {}
+                Err(ICTTimeoutError::Timeout) => break,
+                Err(ICTTimeoutError::Inner(e)) => return Err((count, e)),
+            }
+        }
+        Ok(count)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
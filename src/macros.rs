@@ -0,0 +1,64 @@
+//! Macros.
+
+/// Write a labelled hex dump of some data to a `MutBlockingTx`, in debug
+/// builds only.
+///
+/// The label is written on a line of its own, followed by the output of
+/// `SerialExt::puts_hex_dump`. The port expression is evaluated once, and
+/// any error from the port is discarded. When `debug_assertions` is off, the
+/// macro expands to nothing and none of its arguments are evaluated.
+///
+/// ```
+/// # #[macro_use] extern crate embedded_serial;
+/// # use embedded_serial::MutBlockingTx;
+/// # struct Uart;
+/// # impl MutBlockingTx for Uart {
+/// #     type Error = ();
+/// #     fn putc(&mut self, _ch: u8) -> Result<(), ()> { Ok(()) }
+/// # }
+/// # fn main() {
+/// let mut uart = Uart;
+/// serial_hexdump!(uart, "packet", &[0xCA, 0xFE]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! serial_hexdump {
+    ($uart:expr, $label:expr, $data:expr) => {{
+        #[cfg(debug_assertions)]
+        {
+            #[allow(unused_imports)]
+            use $crate::MutBlockingTx;
+            #[allow(unused_imports)]
+            use $crate::ext::SerialExt;
+            let uart = &mut $uart;
+            let _ = uart.puts($label.as_bytes());
+            let _ = uart.puts(b"\r\n");
+            let _ = uart.puts_hex_dump(&$data[..]);
+        }
+    }};
+}
+
+/// Call a function returning an `nb::Result` until it stops returning
+/// `nb::Error::WouldBlock`, in the style of the `nb` crate's `block!`.
+///
+/// The result is `Ok` with the value, or `Err` with the error from
+/// `nb::Error::Other`.
+#[macro_export]
+macro_rules! nb_block {
+    ($e:expr) => {
+        loop {
+            #[allow(unreachable_patterns)]
+            match $e {
+                Err($crate::nb::Error::WouldBlock) => {}
+                Err($crate::nb::Error::Other(e)) => break Err(e),
+                Ok(x) => break Ok(x),
+            }
+        }
+    };
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,181 @@
+//! # Modbus RTU
+//!
+//! Modbus RTU sends each message as a binary frame: the server address, a
+//! function code, the data and a CRC-16/MODBUS (sent least significant
+//! octet first). There is no length field or delimiter. Instead, frames are
+//! separated by at least 3.5 character times of silence.
+//!
+//! `ModbusRtu` handles the framing for a Modbus master (client). Both
+//! requests and responses use the same port, so the port's transmit and
+//! receive errors must be the same type.
+
+use crate::crc::crc16_ibm;
+use crate::{MutBlockingRxWithTimeout, MutBlockingTx};
+
+/// The longest a Modbus RTU frame can be, including the address and CRC.
+pub const MAX_FRAME_LEN: usize = 256;
+
+/// The most data which fits in a request, after the address, function code
+/// and CRC.
+pub const MAX_DATA_LEN: usize = MAX_FRAME_LEN - 4;
+
+// Set in the function code of a response to report an exception.
+const EXCEPTION_FLAG: u8 = 0x80;
+
+/// A frame received by `ModbusRtu::recv_response`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ModbusFrame<'b> {
+    /// The address of the server which sent the frame.
+    pub addr: u8,
+    /// The function code.
+    pub function: u8,
+    /// The data between the function code and the CRC, in the buffer given
+    /// to `recv_response`.
+    pub data: &'b [u8],
+}
+
+/// The ways in which a Modbus request or response can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ModbusError<E> {
+    /// The request data is longer than `MAX_DATA_LEN`. Nothing was sent.
+    TooLong,
+    /// No response arrived before the timeout.
+    Timeout,
+    /// The response was too short to be a frame.
+    TooShort,
+    /// The response was longer than the buffer provided. The rest of the
+    /// frame was read and discarded.
+    BufferTooSmall,
+    /// The CRC received didn't match the frame.
+    CrcMismatch {
+        /// The CRC calculated over the received frame.
+        expected: u16,
+        /// The CRC which was received.
+        got: u16,
+    },
+    /// The server replied with an exception response.
+    Exception {
+        /// The function code of the request, without the exception flag.
+        function: u8,
+        /// The exception code (e.g. 0x02 for an illegal data address).
+        code: u8,
+    },
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Wraps a port connected to a Modbus RTU bus, for use by the master.
+///
+/// `delay` is called before each request is sent, and should wait for the
+/// 3.5 character inter-frame gap at the bus's baud rate. A response ends
+/// when nothing arrives for `frame_gap`, which should be about the same
+/// length. `response_timeout` is how long to wait for a response to start.
+pub struct ModbusRtu<T, F>
+    where T: MutBlockingRxWithTimeout
+{
+    inner: T,
+    response_timeout: T::Timeout,
+    frame_gap: T::Timeout,
+    delay: F,
+}
+
+impl<T, F> ModbusRtu<T, F>
+    where T: MutBlockingTx + MutBlockingRxWithTimeout<Error = <T as MutBlockingTx>::Error>,
+          F: FnMut()
+{
+    /// Wrap a port.
+    pub fn new(inner: T,
+               response_timeout: T::Timeout,
+               frame_gap: T::Timeout,
+               delay: F)
+               -> ModbusRtu<T, F> {
+        ModbusRtu {
+            inner,
+            response_timeout,
+            frame_gap,
+            delay,
+        }
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Wait for the inter-frame gap, then send a request to the server at
+    /// `addr`.
+    pub fn send_request(&mut self,
+                        addr: u8,
+                        function: u8,
+                        data: &[u8])
+                        -> Result<(), ModbusError<<T as MutBlockingTx>::Error>> {
+        if data.len() > MAX_DATA_LEN {
+            return Err(ModbusError::TooLong);
+        }
+        (self.delay)();
+        let mut crc = 0xFFFF;
+        for octet in [addr, function].iter().chain(data) {
+            self.inner.putc(*octet).map_err(ModbusError::Inner)?;
+            crc = crc16_ibm(crc, *octet);
+        }
+        for octet in &crc.to_le_bytes() {
+            self.inner.putc(*octet).map_err(ModbusError::Inner)?;
+        }
+        Ok(())
+    }
+
+    /// Read one frame into `buf`, which should have room for
+    /// `MAX_FRAME_LEN` octets to be sure of holding any response.
+    ///
+    /// An exception response is returned as `ModbusError::Exception`.
+    pub fn recv_response<'b>(&mut self,
+                             buf: &'b mut [u8])
+                             -> Result<ModbusFrame<'b>, ModbusError<<T as MutBlockingTx>::Error>> {
+        let first = self.inner.getc_wait(&self.response_timeout).map_err(ModbusError::Inner)?;
+        let mut next = first.ok_or(ModbusError::Timeout)?;
+        let mut len = 0;
+        let mut too_small = false;
+        loop {
+            match buf.get_mut(len) {
+                Some(space) => {
+                    *space = next;
+                    len += 1;
+                }
+                None => too_small = true,
+            }
+            next = match self.inner.getc_wait(&self.frame_gap).map_err(ModbusError::Inner)? {
+                Some(ch) => ch,
+                None => break,
+            };
+        }
+        if too_small {
+            return Err(ModbusError::BufferTooSmall);
+        }
+        if len < 4 {
+            return Err(ModbusError::TooShort);
+        }
+        let (frame, trailer) = buf[..len].split_at(len - 2);
+        let expected = frame.iter().fold(0xFFFF, |crc, octet| crc16_ibm(crc, *octet));
+        let got = u16::from_le_bytes([trailer[0], trailer[1]]);
+        if got != expected {
+            return Err(ModbusError::CrcMismatch { expected, got });
+        }
+        if frame[1] & EXCEPTION_FLAG != 0 {
+            return Err(ModbusError::Exception {
+                function: frame[1] & !EXCEPTION_FLAG,
+                code: frame.get(2).cloned().unwrap_or(0),
+            });
+        }
+        Ok(ModbusFrame {
+            addr: frame[0],
+            function: frame[1],
+            data: &frame[2..],
+        })
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,188 @@
+//! # Multi-drop buses
+//!
+//! On a multi-drop bus (RS-485, LIN, etc) several nodes share one serial
+//! line and each frame is addressed to a particular node. The
+//! `SerialAddress` type keeps node addresses distinct from ordinary data
+//! octets.
+
+use core::fmt;
+use core::time::Duration;
+
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// The address of a node on a multi-drop serial bus.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SerialAddress(pub u8);
+
+impl SerialAddress {
+    /// The address which every node on the bus listens to.
+    pub const BROADCAST: SerialAddress = SerialAddress(0xFF);
+
+    /// Returns true if this is the broadcast address.
+    pub fn is_broadcast(&self) -> bool {
+        *self == SerialAddress::BROADCAST
+    }
+}
+
+impl fmt::UpperHex for SerialAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.0, f)
+    }
+}
+
+impl fmt::LowerHex for SerialAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for SerialAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{:02X}", self.0)
+    }
+}
+
+impl fmt::Debug for SerialAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SerialAddress(0x{:02X})", self.0)
+    }
+}
+
+/// Implementors of this trait can send a frame of data to a particular node
+/// on a multi-drop bus.
+pub trait MultiDropTx {
+    /// The error type returned if a function fails.
+    type Error;
+
+    /// Send `data` to the node at `addr`, blocking until it has all been
+    /// accepted for transmission.
+    /// If this returns `Ok(())`, all the data was sent.
+    /// Otherwise you get number of octets sent and the error.
+    fn send_to(&mut self, addr: SerialAddress, data: &[u8]) -> Result<(), (usize, Self::Error)>;
+}
+
+/// Implementors of this trait can receive the frames on a multi-drop bus
+/// which are addressed to a particular node.
+pub trait MultiDropRx {
+    /// The error type returned if a function fails.
+    type Error;
+
+    /// Block until a frame addressed to `addr` (or to the broadcast address)
+    /// arrives, and read it into `buffer`. Frames for other nodes are
+    /// skipped.
+    ///
+    /// Returns the number of octets in the frame, or an error and the number
+    /// of octets read before the error. Octets which don't fit in `buffer`
+    /// are discarded.
+    fn recv_addressed(&mut self,
+                      addr: SerialAddress,
+                      buffer: &mut [u8])
+                      -> Result<usize, (usize, Self::Error)>;
+}
+
+/// Implementors of this trait control the direction of a half-duplex bus,
+/// such as RS-485, where the transmitter has to be enabled before sending
+/// and disabled afterwards so other nodes can talk.
+pub trait HalfDuplex: MutBlockingTx {
+    /// Switch the bus driver to transmit, e.g. by asserting the DE pin.
+    fn enable_tx(&mut self) -> Result<(), Self::Error>;
+
+    /// Switch the bus driver back to receive, e.g. by de-asserting the DE
+    /// pin.
+    fn enable_rx(&mut self) -> Result<(), Self::Error>;
+
+    /// How long the direction pin takes to settle after switching. This
+    /// crate has no way to wait, so `enable_tx` and `enable_rx` should
+    /// include the delay themselves; it is published here for generic code
+    /// which needs to account for it. The default is zero.
+    fn turnaround_delay(&self) -> Duration {
+        Duration::from_secs(0)
+    }
+
+    /// Send `data` safely: enable the transmitter, send, wait for the last
+    /// octet to leave the shift register, then go back to receive.
+    ///
+    /// If this returns `Ok(())`, all the data was sent. Otherwise you get
+    /// number of octets sent and the error. If sending fails, switching back
+    /// to receive is still attempted, so the bus isn't left driven.
+    fn send_rs485(&mut self, data: &[u8]) -> Result<(), (usize, Self::Error)> {
+        self.enable_tx().map_err(|e| (0, e))?;
+        let result = self.puts(data).and_then(|()| self.flush().map_err(|e| (data.len(), e)));
+        let rx = self.enable_rx();
+        result?;
+        rx.map_err(|e| (data.len(), e))
+    }
+}
+
+/// Implementors of this trait send and receive 9-bit characters, where the
+/// ninth bit marks an address character (set) or a data character (clear).
+/// UARTs with a 9-bit mode can use this to filter addresses in hardware.
+pub trait NinebitSerial {
+    /// The error type returned if a function fails.
+    type Error;
+
+    /// Write a single 9-bit character to the port's transmitter, blocking
+    /// until it can be stored in the buffer.
+    fn putc9(&mut self, byte: u8, address_bit: bool) -> Result<(), Self::Error>;
+
+    /// Read a single 9-bit character from the port's receiver, blocking until
+    /// one is available. Returns the low eight bits and the address bit.
+    fn getc9(&mut self) -> Result<(u8, bool), Self::Error>;
+}
+
+/// The ways in which a `NinebitAdapter` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NinebitError<E> {
+    /// The octet to be sent used the top bit, which the adapter needs for the
+    /// address bit.
+    DataOutOfRange(u8),
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Provides `NinebitSerial` over an ordinary 8-bit port, for hardware
+/// without a 9-bit mode.
+///
+/// The address bit is carried in the top bit of each octet, so only seven
+/// bits of data are available per character. Both ends of the link must
+/// agree to use this encoding.
+pub struct NinebitAdapter<T> {
+    inner: T,
+}
+
+impl<T> NinebitAdapter<T> {
+    /// Wrap a port.
+    pub fn new(inner: T) -> NinebitAdapter<T> {
+        NinebitAdapter { inner }
+    }
+
+    /// Release the wrapped port.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> NinebitSerial for NinebitAdapter<T>
+    where T: MutBlockingTx + MutBlockingRx<Error = <T as MutBlockingTx>::Error>
+{
+    type Error = NinebitError<<T as MutBlockingTx>::Error>;
+
+    fn putc9(&mut self, byte: u8, address_bit: bool) -> Result<(), Self::Error> {
+        if byte & 0x80 != 0 {
+            return Err(NinebitError::DataOutOfRange(byte));
+        }
+        let ch = if address_bit { byte | 0x80 } else { byte };
+        self.inner.putc(ch).map_err(NinebitError::Inner)
+    }
+
+    fn getc9(&mut self) -> Result<(u8, bool), Self::Error> {
+        let ch = self.inner.getc().map_err(NinebitError::Inner)?;
+        Ok((ch & 0x7F, ch & 0x80 != 0))
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
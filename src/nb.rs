@@ -0,0 +1,42 @@
+//! # Non-blocking results
+//!
+//! A stand-in for the `nb` crate's result type, without the dependency.
+//! `SerialNonBlockingTxExt::putc_nb` and `SerialNonBlockingRxExt::getc_nb`
+//! return it, and the `nb_block!` macro spins on them until they succeed:
+//!
+//! ```
+//! # #[macro_use] extern crate embedded_serial;
+//! use embedded_serial::ext::{SerialNonBlockingRxExt, SerialNonBlockingTxExt};
+//! use embedded_serial::nb;
+//! use embedded_serial::null::NullSerial;
+//!
+//! # fn main() {
+//! let mut port = NullSerial;
+//! nb_block!(port.putc_nb(0x42)).unwrap();
+//! assert_eq!(port.getc_nb(), Err(nb::Error::WouldBlock));
+//! # }
+//! ```
+
+/// The error half of a non-blocking `Result`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error<E> {
+    /// The operation failed.
+    Other(E),
+    /// The operation couldn't be done without blocking. Try it again later.
+    WouldBlock,
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(error: E) -> Error<E> {
+        Error::Other(error)
+    }
+}
+
+/// The result of a non-blocking operation.
+pub type Result<T, E> = core::result::Result<T, Error<E>>;
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,148 @@
+//! # NMEA-0183
+//!
+//! GPS (and other GNSS) modules report their position as NMEA-0183
+//! sentences, such as
+//! `$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47`.
+//! Each starts with `$`, a two letter talker ID and a sentence type, has
+//! comma separated fields, and ends with `*`, a two digit hex checksum and
+//! `\r\n`. The checksum is the XOR of every octet between `$` and `*`.
+
+use crate::codec::hex_value;
+use crate::MutBlockingRx;
+
+/// A sentence received by `Nmea0183Reader::read_sentence`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct NmeaSentence<'b> {
+    /// The talker ID, e.g. `GP` for GPS or `GN` for combined GNSS.
+    pub talker: &'b [u8],
+    /// The sentence type, e.g. `GGA`. For proprietary sentences (whose
+    /// talker is `P` and a manufacturer code) this is the rest of the
+    /// address after the first two characters.
+    pub type_code: &'b [u8],
+    /// The fields, still separated by commas, without the checksum.
+    pub fields: &'b [u8],
+}
+
+impl<'b> NmeaSentence<'b> {
+    /// Get field `idx`, counting from zero. Empty fields are returned as
+    /// empty slices; `None` means the sentence has fewer fields.
+    pub fn field(&self, idx: usize) -> Option<&'b [u8]> {
+        self.fields.split(|ch| *ch == b',').nth(idx)
+    }
+}
+
+/// The ways in which reading an NMEA sentence can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NmeaError<E> {
+    /// The sentence was longer than the buffer. It was read and discarded.
+    TooLong,
+    /// The sentence had no checksum, or wasn't laid out as a sentence.
+    Malformed,
+    /// The checksum received didn't match the sentence. The sentence was
+    /// probably corrupted on the way, and can be discarded.
+    ChecksumMismatch {
+        /// The checksum calculated over the sentence.
+        expected: u8,
+        /// The checksum which was received.
+        got: u8,
+    },
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Wraps a receiver connected to a GPS module and reads NMEA-0183 sentences
+/// of up to `N` octets (82 is the longest the standard allows, including
+/// `$` and `\r\n`).
+///
+/// ```
+/// use embedded_serial::nmea::Nmea0183Reader;
+/// use embedded_serial::testing::SliceRx;
+///
+/// let data = b"$GPGLL,4916.45,N,12311.12,W,225444,A,*1D\r\n";
+/// let mut gps = Nmea0183Reader::<_, 82>::new(SliceRx::new(data));
+/// let mut buf = [0; 82];
+/// let sentence = gps.read_sentence(&mut buf).unwrap();
+/// assert_eq!(sentence.talker, b"GP");
+/// assert_eq!(sentence.type_code, b"GLL");
+/// assert_eq!(sentence.field(2), Some(&b"12311.12"[..]));
+/// ```
+pub struct Nmea0183Reader<T, const N: usize> {
+    inner: T,
+}
+
+impl<T, const N: usize> Nmea0183Reader<T, N>
+    where T: MutBlockingRx
+{
+    /// Wrap a receiver.
+    pub fn new(inner: T) -> Nmea0183Reader<T, N> {
+        Nmea0183Reader { inner }
+    }
+
+    /// Release the wrapped receiver.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Read the next sentence into `buf`, blocking until it has arrived.
+    /// Anything before the `$` which starts the sentence is discarded.
+    pub fn read_sentence<'b>(&mut self,
+                             buf: &'b mut [u8; N])
+                             -> Result<NmeaSentence<'b>, NmeaError<T::Error>> {
+        while self.getc()? != b'$' {}
+        let mut len = 0;
+        let mut too_long = false;
+        loop {
+            let ch = self.getc()?;
+            match ch {
+                b'\n' => break,
+                b'\r' => {}
+                _ if len < N => {
+                    buf[len] = ch;
+                    len += 1;
+                }
+                _ => too_long = true,
+            }
+        }
+        if too_long {
+            return Err(NmeaError::TooLong);
+        }
+        parse_sentence(&buf[..len])
+    }
+
+    fn getc(&mut self) -> Result<u8, NmeaError<T::Error>> {
+        self.inner.getc().map_err(NmeaError::Inner)
+    }
+}
+
+/// Check and split up the text between `$` and `\r\n`.
+fn parse_sentence<E>(text: &[u8]) -> Result<NmeaSentence<'_>, NmeaError<E>> {
+    let star = text.iter().rposition(|ch| *ch == b'*').ok_or(NmeaError::Malformed)?;
+    let (body, checksum) = (&text[..star], &text[star + 1..]);
+    let got = match checksum {
+        [hi, lo] => match (hex_value(*hi), hex_value(*lo)) {
+            (Some(hi), Some(lo)) => hi << 4 | lo,
+            _ => return Err(NmeaError::Malformed),
+        },
+        _ => return Err(NmeaError::Malformed),
+    };
+    let expected = body.iter().fold(0, |sum, ch| sum ^ ch);
+    if got != expected {
+        return Err(NmeaError::ChecksumMismatch { expected, got });
+    }
+    let comma = body.iter().position(|ch| *ch == b',').unwrap_or(body.len());
+    let address = &body[..comma];
+    if address.len() < 3 {
+        return Err(NmeaError::Malformed);
+    }
+    Ok(NmeaSentence {
+        talker: &address[..2],
+        type_code: &address[2..],
+        fields: body.get(comma + 1..).unwrap_or(&[]),
+    })
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,235 @@
+//! # Null port
+//!
+//! A port which throws away everything sent to it and never receives
+//! anything.
+
+use crate::error::NoError;
+use crate::{ImmutBlockingRxWithTimeout, ImmutBlockingTx, ImmutBlockingTxWithTimeout,
+            ImmutNonBlockingRx, ImmutNonBlockingRxWithTimeout, ImmutNonBlockingTx,
+            ImmutNonBlockingTxWithTimeout, MutBlockingRxWithTimeout, MutBlockingTx,
+            MutBlockingTxWithTimeout, MutNonBlockingRx, MutNonBlockingRxWithTimeout,
+            MutNonBlockingTx, RxReady};
+
+/// A zero-sized port which silently discards all transmitted octets, and
+/// never receives any. Use it in place of a real port to turn serial output
+/// off at no cost - for example, debug logging in a release build.
+///
+/// Every Tx trait is implemented and always succeeds at once. The timeout
+/// and non-blocking Rx traits are implemented and always report that
+/// nothing arrived. The plain blocking Rx traits are not implemented, as
+/// they would have to block for ever.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct NullSerial;
+
+impl MutBlockingTx for NullSerial {
+    type Error = NoError;
+
+    fn putc(&mut self, _ch: u8) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn puts<I>(&mut self, _data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        Ok(())
+    }
+}
+
+impl MutBlockingTxWithTimeout for NullSerial {
+    type Timeout = ();
+    type Error = NoError;
+
+    fn putc_wait(&mut self, ch: u8, _timeout: &()) -> Result<Option<u8>, Self::Error> {
+        Ok(Some(ch))
+    }
+
+    fn puts_wait<I>(&mut self, data: &I, _timeout: &()) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        Ok(data.as_ref().len())
+    }
+}
+
+impl MutNonBlockingTx for NullSerial {
+    type Error = NoError;
+
+    fn putc_try(&mut self, ch: u8) -> Result<Option<u8>, Self::Error> {
+        Ok(Some(ch))
+    }
+
+    fn puts_try<I>(&mut self, data: &I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        Ok(data.as_ref().len())
+    }
+}
+
+impl MutBlockingRxWithTimeout for NullSerial {
+    type Timeout = ();
+    type Error = NoError;
+
+    fn getc_wait(&mut self, _timeout: &()) -> Result<Option<u8>, Self::Error> {
+        Ok(None)
+    }
+
+    fn gets_wait<I>(&mut self,
+                    _buffer: &mut I,
+                    _timeout: &())
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        Ok(0)
+    }
+}
+
+impl RxReady for NullSerial {
+    fn wait_for_rx_ready(&mut self, _timeout: &()) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+}
+
+impl MutNonBlockingRx for NullSerial {
+    type Error = NoError;
+
+    fn getc_try(&mut self) -> Result<Option<u8>, Self::Error> {
+        Ok(None)
+    }
+
+    fn gets_try<I>(&mut self, _buffer: &mut I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        Ok(0)
+    }
+}
+
+impl MutNonBlockingRxWithTimeout for NullSerial {
+    type Timeout = ();
+    type Error = NoError;
+
+    fn getc_try_wait(&mut self, _timeout: &()) -> Result<Option<u8>, Self::Error> {
+        Ok(None)
+    }
+
+    fn gets_try_wait<I>(&mut self,
+                        _buffer: &mut I,
+                        _timeout: &())
+                        -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        Ok(0)
+    }
+}
+
+impl ImmutBlockingTx for NullSerial {
+    type Error = NoError;
+
+    fn putc(&self, _ch: u8) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn puts<I>(&self, _data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        Ok(())
+    }
+}
+
+impl ImmutBlockingTxWithTimeout for NullSerial {
+    type Timeout = ();
+    type Error = NoError;
+
+    fn putc_wait(&self, ch: u8, _timeout: &()) -> Result<Option<u8>, Self::Error> {
+        Ok(Some(ch))
+    }
+
+    fn puts_wait<I>(&self, data: &I, _timeout: &()) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        Ok(data.as_ref().len())
+    }
+}
+
+impl ImmutNonBlockingTx for NullSerial {
+    type Error = NoError;
+
+    fn putc_try(&self, ch: u8) -> Result<Option<u8>, Self::Error> {
+        Ok(Some(ch))
+    }
+
+    fn puts_try<I>(&self, data: &I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        Ok(data.as_ref().len())
+    }
+}
+
+impl ImmutNonBlockingTxWithTimeout for NullSerial {
+    type Timeout = ();
+    type Error = NoError;
+
+    fn putc_try_wait(&self, ch: u8, _timeout: &()) -> Result<Option<u8>, Self::Error> {
+        Ok(Some(ch))
+    }
+
+    fn puts_try_wait<I>(&self, data: &I, _timeout: &()) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        Ok(data.as_ref().len())
+    }
+}
+
+impl ImmutBlockingRxWithTimeout for NullSerial {
+    type Timeout = ();
+    type Error = NoError;
+
+    fn getc_wait(&self, _timeout: &()) -> Result<Option<u8>, Self::Error> {
+        Ok(None)
+    }
+
+    fn gets_wait<I>(&self,
+                    _buffer: &mut I,
+                    _timeout: &())
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        Ok(0)
+    }
+}
+
+impl ImmutNonBlockingRx for NullSerial {
+    type Error = NoError;
+
+    fn getc_try(&self) -> Result<Option<u8>, Self::Error> {
+        Ok(None)
+    }
+
+    fn gets_try<I>(&self, _buffer: &mut I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        Ok(0)
+    }
+}
+
+impl ImmutNonBlockingRxWithTimeout for NullSerial {
+    type Timeout = ();
+    type Error = NoError;
+
+    fn getc_try_wait(&self, _timeout: &()) -> Result<Option<u8>, Self::Error> {
+        Ok(None)
+    }
+
+    fn gets_try_wait<I>(&self,
+                        _buffer: &mut I,
+                        _timeout: &())
+                        -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        Ok(0)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,131 @@
+//! # Single wire serial
+//!
+//! Some designs have no UART to spare, or run Tx and Rx over one wire to
+//! save a pin. `GpioPinSerial` bit-bangs 8N1 frames on a single GPIO pin,
+//! using it both to send and to receive.
+//!
+//! The pin traits here are deliberately minimal, in the style of
+//! `delay::DelayUs`, so they can be implemented on top of whatever GPIO API
+//! the platform has.
+
+use crate::config::Baud;
+use crate::delay::DelayUs;
+use crate::error::{HardwareError, NoError};
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// Implementors of this trait can drive a GPIO pin high or low.
+pub trait OutputPin {
+    /// Drive the pin low.
+    fn set_low(&mut self);
+
+    /// Drive the pin high. On a shared line this should release it, so
+    /// another device can pull it low.
+    fn set_high(&mut self);
+}
+
+/// Implementors of this trait can read the level on a GPIO pin.
+pub trait InputPin {
+    /// Returns true if the pin is high.
+    fn is_high(&mut self) -> bool;
+}
+
+/// Bit-bangs 8N1 serial frames on a single pin.
+///
+/// The pin is used as an open drain output with a pull-up, so `set_high`
+/// releases the line and the idle (mark) state is high. Sending drives the
+/// start bit low, then the eight data bits least significant first, then a
+/// high stop bit. Receiving releases the line, waits for a start bit, then
+/// samples each bit in the middle of its bit-time.
+///
+/// Timing comes from `delay`, so the time taken to drive and read the pin
+/// is counted as part of each bit. This is only accurate at low baud
+/// rates, and interrupts should be disabled while a frame is in progress.
+/// Reception blocks, spinning on the pin, until a start bit arrives.
+pub struct GpioPinSerial<P, D> {
+    pin: P,
+    delay: D,
+    bit_us: u32,
+}
+
+impl<P, D> GpioPinSerial<P, D>
+    where P: InputPin + OutputPin,
+          D: DelayUs
+{
+    /// Take a pin and a delay, and run at the given baud rate. The pin is
+    /// released, so the line idles high.
+    pub fn new(mut pin: P, delay: D, baud: Baud) -> GpioPinSerial<P, D> {
+        pin.set_high();
+        let bit_us = (1_000_000 + baud.0 / 2) / baud.0.max(1);
+        GpioPinSerial { pin, delay, bit_us }
+    }
+
+    /// Release the pin and the delay.
+    pub fn into_inner(self) -> (P, D) {
+        (self.pin, self.delay)
+    }
+
+    fn send_bit(&mut self, high: bool) {
+        if high {
+            self.pin.set_high();
+        } else {
+            self.pin.set_low();
+        }
+        self.delay.delay_us(self.bit_us);
+    }
+}
+
+impl<P, D> MutBlockingTx for GpioPinSerial<P, D>
+    where P: InputPin + OutputPin,
+          D: DelayUs
+{
+    type Error = NoError;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.send_bit(false);
+        for bit in 0..8 {
+            self.send_bit(ch & (1 << bit) != 0);
+        }
+        self.send_bit(true);
+        Ok(())
+    }
+}
+
+impl<P, D> MutBlockingRx for GpioPinSerial<P, D>
+    where P: InputPin + OutputPin,
+          D: DelayUs
+{
+    /// Only `HardwareError::Framing` is returned, if the stop bit is low.
+    type Error = HardwareError;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        self.pin.set_high();
+        loop {
+            while self.pin.is_high() {}
+            // Check the start bit is still there half way through, so a
+            // glitch isn't taken for one.
+            self.delay.delay_us(self.bit_us / 2);
+            if !self.pin.is_high() {
+                break;
+            }
+        }
+        let mut ch = 0;
+        for bit in 0..8 {
+            self.delay.delay_us(self.bit_us);
+            if self.pin.is_high() {
+                ch |= 1 << bit;
+            }
+        }
+        self.delay.delay_us(self.bit_us);
+        if self.pin.is_high() {
+            Ok(ch)
+        } else {
+            Err(HardwareError::Framing)
+        }
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,115 @@
+//! # Resumable transfers
+//!
+//! State machines which send or receive a whole buffer through a
+//! non-blocking port, a FIFO-full at a time. Call `poll` from your main loop
+//! or cooperative scheduler until it returns `Ok(true)`; the offset into the
+//! buffer is kept for you between calls.
+
+use crate::{MutNonBlockingRx, MutNonBlockingTx};
+
+/// Sends all of `data` through a `MutNonBlockingTx`, over as many calls to
+/// `poll` as it takes.
+pub struct WriteAll<'a, T>
+    where T: 'a + ?Sized
+{
+    uart: &'a mut T,
+    data: &'a [u8],
+    sent: usize,
+}
+
+impl<'a, T> WriteAll<'a, T>
+    where T: 'a + ?Sized + MutNonBlockingTx
+{
+    /// Prepare to send `data` through `uart`. Nothing is sent until `poll` is
+    /// called.
+    pub fn new(uart: &'a mut T, data: &'a [u8]) -> WriteAll<'a, T> {
+        WriteAll {
+            uart,
+            data,
+            sent: 0,
+        }
+    }
+
+    /// Send as much of what remains as the port will accept right now.
+    /// Returns `Ok(true)` once everything has been sent, or `Ok(false)` if
+    /// there is more to do.
+    ///
+    /// Octets accepted before an error are counted, so calling `poll` again
+    /// after an error carries on from where it failed.
+    pub fn poll(&mut self) -> Result<bool, T::Error> {
+        match self.uart.puts_try(&self.data[self.sent..]) {
+            Ok(count) => self.sent += count,
+            Err((count, e)) => {
+                self.sent += count;
+                return Err(e);
+            }
+        }
+        Ok(self.is_done())
+    }
+
+    /// Returns true once all the data has been sent.
+    pub fn is_done(&self) -> bool {
+        self.sent == self.data.len()
+    }
+
+    /// The number of octets sent so far.
+    pub fn sent(&self) -> usize {
+        self.sent
+    }
+}
+
+/// Fills all of `buffer` from a `MutNonBlockingRx`, over as many calls to
+/// `poll` as it takes.
+pub struct ReadExact<'a, T>
+    where T: 'a + ?Sized
+{
+    uart: &'a mut T,
+    buffer: &'a mut [u8],
+    received: usize,
+}
+
+impl<'a, T> ReadExact<'a, T>
+    where T: 'a + ?Sized + MutNonBlockingRx
+{
+    /// Prepare to fill `buffer` from `uart`. Nothing is read until `poll` is
+    /// called.
+    pub fn new(uart: &'a mut T, buffer: &'a mut [u8]) -> ReadExact<'a, T> {
+        ReadExact {
+            uart,
+            buffer,
+            received: 0,
+        }
+    }
+
+    /// Read whatever is waiting, up to the space left in the buffer. Returns
+    /// `Ok(true)` once the buffer is full, or `Ok(false)` if more is needed.
+    ///
+    /// Octets read before an error are kept, so calling `poll` again after an
+    /// error carries on from where it failed.
+    pub fn poll(&mut self) -> Result<bool, T::Error> {
+        match self.uart.gets_remainder(self.buffer, self.received) {
+            Ok(count) => self.received = count,
+            Err((count, e)) => {
+                self.received = count;
+                return Err(e);
+            }
+        }
+        Ok(self.is_done())
+    }
+
+    /// Returns true once the buffer is full.
+    pub fn is_done(&self) -> bool {
+        self.received == self.buffer.len()
+    }
+
+    /// The octets received so far.
+    pub fn received(&self) -> &[u8] {
+        &self.buffer[..self.received]
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,393 @@
+//! Forwarding impls, so that a reference to a port can be used wherever
+//! the port itself could be - `&mut T` for the `Mut*` traits, and `&T` for
+//! the `Immut*` traits.
+
+use crate::{ImmutBlockingRx, ImmutBlockingRxWithTimeout, ImmutBlockingTx,
+            ImmutBlockingTxWithTimeout, ImmutNonBlockingRx,
+            ImmutNonBlockingRxWithTimeout, ImmutNonBlockingTx,
+            ImmutNonBlockingTxWithTimeout, MutBlockingRx,
+            MutBlockingRxWithTimeout, MutBlockingTx, MutBlockingTxWithTimeout,
+            MutNonBlockingRx, MutNonBlockingRxWithTimeout, MutNonBlockingTx,
+            RxReady};
+
+impl<T> MutBlockingTx for &mut T
+    where T: ?Sized + MutBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        (**self).putc(ch)
+    }
+
+    fn puts<I>(&mut self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        (**self).puts(data)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn puts_repeat(&mut self, byte: u8, count: usize) -> Result<(), (usize, Self::Error)> {
+        (**self).puts_repeat(byte, count)
+    }
+}
+
+impl<T> MutBlockingTxWithTimeout for &mut T
+    where T: ?Sized + MutBlockingTxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn putc_wait(&mut self, ch: u8, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        (**self).putc_wait(ch, timeout)
+    }
+
+    fn puts_wait<I>(&mut self,
+                    data: &I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        (**self).puts_wait(data, timeout)
+    }
+
+    fn flush_wait(&mut self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        (**self).flush_wait(timeout)
+    }
+
+    fn puts_repeat_wait(&mut self,
+                        byte: u8,
+                        count: usize,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)> {
+        (**self).puts_repeat_wait(byte, count, timeout)
+    }
+}
+
+impl<T> MutNonBlockingTx for &mut T
+    where T: ?Sized + MutNonBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc_try(&mut self, ch: u8) -> Result<Option<u8>, Self::Error> {
+        (**self).putc_try(ch)
+    }
+
+    fn puts_try<I>(&mut self, data: &I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        (**self).puts_try(data)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn puts_repeat_try(&mut self, byte: u8, count: usize) -> Result<usize, (usize, Self::Error)> {
+        (**self).puts_repeat_try(byte, count)
+    }
+}
+
+impl<T> MutBlockingRx for &mut T
+    where T: ?Sized + MutBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        (**self).getc()
+    }
+
+    fn gets<I>(&mut self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        (**self).gets(buffer)
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        (**self).bytes_available()
+    }
+}
+
+impl<T> MutBlockingRxWithTimeout for &mut T
+    where T: ?Sized + MutBlockingRxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn getc_wait(&mut self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        (**self).getc_wait(timeout)
+    }
+
+    fn gets_wait<I>(&mut self,
+                    buffer: &mut I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        (**self).gets_wait(buffer, timeout)
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        (**self).bytes_available()
+    }
+}
+
+impl<T> RxReady for &mut T
+    where T: ?Sized + RxReady
+{
+    fn wait_for_rx_ready(&mut self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        (**self).wait_for_rx_ready(timeout)
+    }
+}
+
+impl<T> MutNonBlockingRx for &mut T
+    where T: ?Sized + MutNonBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc_try(&mut self) -> Result<Option<u8>, Self::Error> {
+        (**self).getc_try()
+    }
+
+    fn gets_try<I>(&mut self, buffer: &mut I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        (**self).gets_try(buffer)
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        (**self).bytes_available()
+    }
+}
+
+impl<T> MutNonBlockingRxWithTimeout for &mut T
+    where T: ?Sized + MutNonBlockingRxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn getc_try_wait(&mut self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        (**self).getc_try_wait(timeout)
+    }
+
+    fn gets_try_wait<I>(&mut self,
+                        buffer: &mut I,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        (**self).gets_try_wait(buffer, timeout)
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        (**self).bytes_available()
+    }
+}
+
+impl<T> ImmutBlockingTx for &T
+    where T: ?Sized + ImmutBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc(&self, ch: u8) -> Result<(), Self::Error> {
+        (**self).putc(ch)
+    }
+
+    fn puts<I>(&self, data: &I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        (**self).puts(data)
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn puts_repeat(&self, byte: u8, count: usize) -> Result<(), (usize, Self::Error)> {
+        (**self).puts_repeat(byte, count)
+    }
+}
+
+impl<T> ImmutBlockingTxWithTimeout for &T
+    where T: ?Sized + ImmutBlockingTxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn putc_wait(&self, ch: u8, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        (**self).putc_wait(ch, timeout)
+    }
+
+    fn puts_wait<I>(&self,
+                    data: &I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        (**self).puts_wait(data, timeout)
+    }
+
+    fn flush_wait(&self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        (**self).flush_wait(timeout)
+    }
+
+    fn puts_repeat_wait(&self,
+                        byte: u8,
+                        count: usize,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)> {
+        (**self).puts_repeat_wait(byte, count, timeout)
+    }
+}
+
+impl<T> ImmutNonBlockingTx for &T
+    where T: ?Sized + ImmutNonBlockingTx
+{
+    type Error = T::Error;
+
+    fn putc_try(&self, ch: u8) -> Result<Option<u8>, Self::Error> {
+        (**self).putc_try(ch)
+    }
+
+    fn puts_try<I>(&self, data: &I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        (**self).puts_try(data)
+    }
+
+    fn flush(&self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn puts_repeat_try(&self, byte: u8, count: usize) -> Result<usize, (usize, Self::Error)> {
+        (**self).puts_repeat_try(byte, count)
+    }
+}
+
+impl<T> ImmutNonBlockingTxWithTimeout for &T
+    where T: ?Sized + ImmutNonBlockingTxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn putc_try_wait(&self, ch: u8, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        (**self).putc_try_wait(ch, timeout)
+    }
+
+    fn puts_try_wait<I>(&self,
+                        data: &I,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsRef<[u8]>
+    {
+        (**self).puts_try_wait(data, timeout)
+    }
+
+    fn flush_wait(&self, timeout: &Self::Timeout) -> Result<bool, Self::Error> {
+        (**self).flush_wait(timeout)
+    }
+
+    fn puts_repeat_try_wait(&self,
+                            byte: u8,
+                            count: usize,
+                            timeout: &Self::Timeout)
+                            -> Result<usize, (usize, Self::Error)> {
+        (**self).puts_repeat_try_wait(byte, count, timeout)
+    }
+}
+
+impl<T> ImmutBlockingRx for &T
+    where T: ?Sized + ImmutBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc(&self) -> Result<u8, Self::Error> {
+        (**self).getc()
+    }
+
+    fn gets<I>(&self, buffer: &mut I) -> Result<(), (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        (**self).gets(buffer)
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        (**self).bytes_available()
+    }
+}
+
+impl<T> ImmutBlockingRxWithTimeout for &T
+    where T: ?Sized + ImmutBlockingRxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn getc_wait(&self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        (**self).getc_wait(timeout)
+    }
+
+    fn gets_wait<I>(&self,
+                    buffer: &mut I,
+                    timeout: &Self::Timeout)
+                    -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        (**self).gets_wait(buffer, timeout)
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        (**self).bytes_available()
+    }
+}
+
+impl<T> ImmutNonBlockingRx for &T
+    where T: ?Sized + ImmutNonBlockingRx
+{
+    type Error = T::Error;
+
+    fn getc_try(&self) -> Result<Option<u8>, Self::Error> {
+        (**self).getc_try()
+    }
+
+    fn gets_try<I>(&self, buffer: &mut I) -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        (**self).gets_try(buffer)
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        (**self).bytes_available()
+    }
+}
+
+impl<T> ImmutNonBlockingRxWithTimeout for &T
+    where T: ?Sized + ImmutNonBlockingRxWithTimeout
+{
+    type Timeout = T::Timeout;
+    type Error = T::Error;
+
+    fn getc_try_wait(&self, timeout: &Self::Timeout) -> Result<Option<u8>, Self::Error> {
+        (**self).getc_try_wait(timeout)
+    }
+
+    fn gets_try_wait<I>(&self,
+                        buffer: &mut I,
+                        timeout: &Self::Timeout)
+                        -> Result<usize, (usize, Self::Error)>
+        where I: ?Sized + AsMut<[u8]>
+    {
+        (**self).gets_try_wait(buffer, timeout)
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        (**self).bytes_available()
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
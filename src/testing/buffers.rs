@@ -0,0 +1,110 @@
+//! Fixed buffers to capture transmitted data and supply received data.
+
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// The ways in which a `VecTx` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VecTxError {
+    /// An octet was sent after all `N` octets of the buffer had been used.
+    Full,
+}
+
+/// A transmitter which keeps everything sent to it, up to `N` octets, for
+/// checking once the code under test has run.
+///
+/// ```
+/// use embedded_serial::{MutBlockingRx, MutBlockingTx};
+/// use embedded_serial::testing::{SliceRx, VecTx};
+///
+/// let mut tx = VecTx::<16>::new();
+/// let mut rx = SliceRx::new(b"OK");
+/// tx.puts(b"AT\r\n").unwrap();
+/// assert_eq!(rx.getc(), Ok(b'O'));
+/// assert_eq!(tx.as_slice(), b"AT\r\n");
+/// ```
+pub struct VecTx<const N: usize> {
+    buffer: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> VecTx<N> {
+    /// Create an empty transmitter.
+    pub fn new() -> VecTx<N> {
+        VecTx {
+            buffer: [0; N],
+            len: 0,
+        }
+    }
+
+    /// The octets sent so far.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+
+    /// Forget everything sent so far.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Default for VecTx<N> {
+    fn default() -> VecTx<N> {
+        VecTx::new()
+    }
+}
+
+impl<const N: usize> MutBlockingTx for VecTx<N> {
+    type Error = VecTxError;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        let space = self.buffer.get_mut(self.len).ok_or(VecTxError::Full)?;
+        *space = ch;
+        self.len += 1;
+        Ok(())
+    }
+}
+
+/// The ways in which a `SliceRx` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SliceRxError {
+    /// A read was attempted after all the data had been received. A real
+    /// port would block for ever, so this is reported instead.
+    Exhausted,
+}
+
+/// A receiver which hands out the octets of a slice, in order.
+pub struct SliceRx<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> SliceRx<'a> {
+    /// Create a receiver which will receive `data`.
+    pub fn new(data: &'a [u8]) -> SliceRx<'a> {
+        SliceRx { data }
+    }
+
+    /// The octets which haven't been received yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<'a> MutBlockingRx for SliceRx<'a> {
+    type Error = SliceRxError;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        let (first, rest) = self.data.split_first().ok_or(SliceRxError::Exhausted)?;
+        self.data = rest;
+        Ok(*first)
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        Ok(self.data.len())
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
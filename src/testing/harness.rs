@@ -0,0 +1,225 @@
+//! A conformance test harness for looped-back serial ports.
+
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// The data sent by the echo and framing tests. It covers the all-zeros,
+/// all-ones and alternating bit patterns, and the usual control characters.
+const TEST_PATTERN: &[u8] = b"\x00\x55\xAA\xFF\x11\x13\r\nThe quick brown fox\x7F\x80";
+
+/// The number of octets sent by the throughput test.
+const THROUGHPUT_OCTETS: u32 = 64;
+
+/// The most test results a `TestReport` can name.
+const MAX_FAILED_NAMES: usize = 16;
+
+/// The largest frame a `FramingStrategy` may produce in the framing test.
+const MAX_FRAME: usize = 128;
+
+/// A way of wrapping a payload in a frame, for `TestHarness::run_framing_test`.
+pub trait FramingStrategy {
+    /// The name of the framing scheme.
+    fn name(&self) -> &'static str;
+
+    /// Encode `payload` into `out`, returning the length of the frame or
+    /// `None` if it doesn't fit.
+    fn encode(&self, payload: &[u8], out: &mut [u8]) -> Option<usize>;
+
+    /// Decode `frame` into `out`, returning the length of the payload or
+    /// `None` if the frame is invalid or doesn't fit.
+    fn decode(&self, frame: &[u8], out: &mut [u8]) -> Option<usize>;
+}
+
+/// The framing `run_all` uses unless `TestHarness::with_framer` gives
+/// another: a single length octet, then the payload.
+struct LengthPrefixed;
+
+impl FramingStrategy for LengthPrefixed {
+    fn name(&self) -> &'static str {
+        "length prefixed"
+    }
+
+    fn encode(&self, payload: &[u8], out: &mut [u8]) -> Option<usize> {
+        if payload.len() > usize::from(u8::MAX) || payload.len() >= out.len() {
+            return None;
+        }
+        out[0] = payload.len() as u8;
+        out[1..=payload.len()].copy_from_slice(payload);
+        Some(payload.len() + 1)
+    }
+
+    fn decode(&self, frame: &[u8], out: &mut [u8]) -> Option<usize> {
+        let (len, payload) = frame.split_first()?;
+        let len = usize::from(*len);
+        if payload.len() != len || len > out.len() {
+            return None;
+        }
+        out[..len].copy_from_slice(payload);
+        Some(len)
+    }
+}
+
+/// The results of `TestHarness::run_all`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestReport {
+    /// The number of tests which passed.
+    pub passed: usize,
+    /// The number of tests which failed.
+    pub failed: usize,
+    failed_names: [&'static str; MAX_FAILED_NAMES],
+}
+
+impl TestReport {
+    fn new() -> TestReport {
+        TestReport {
+            passed: 0,
+            failed: 0,
+            failed_names: [""; MAX_FAILED_NAMES],
+        }
+    }
+
+    fn record(&mut self, name: &'static str, passed: bool) {
+        if passed {
+            self.passed += 1;
+        } else {
+            if self.failed < MAX_FAILED_NAMES {
+                self.failed_names[self.failed] = name;
+            }
+            self.failed += 1;
+        }
+    }
+
+    /// The names of the tests which failed (at most the first sixteen).
+    pub fn failed_names(&self) -> &[&'static str] {
+        let count = if self.failed < MAX_FAILED_NAMES {
+            self.failed
+        } else {
+            MAX_FAILED_NAMES
+        };
+        &self.failed_names[..count]
+    }
+
+    /// Returns true if every test passed.
+    pub fn all_passed(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+/// Runs a set of standard tests against a serial port whose transmitter is
+/// looped back to its receiver (either in hardware, or with a jumper wire).
+///
+/// Every octet sent is expected to come straight back, so the tests send one
+/// octet at a time and read it back before sending the next. A port which
+/// isn't looped back will block forever in `getc`.
+pub struct TestHarness<T> {
+    port: T,
+    micros: fn() -> u32,
+    target_bps: u32,
+    framer: &'static dyn FramingStrategy,
+}
+
+impl<T> TestHarness<T>
+    where T: MutBlockingTx + MutBlockingRx
+{
+    /// Create a harness for the given port. `micros` must return a
+    /// free-running microsecond count, which is used to time the throughput
+    /// test.
+    pub fn new(port: T, micros: fn() -> u32) -> TestHarness<T> {
+        TestHarness {
+            port,
+            micros,
+            target_bps: 9600,
+            framer: &LengthPrefixed,
+        }
+    }
+
+    /// Set the throughput `run_all` expects, in bits per second of payload.
+    /// The default is 9600.
+    pub fn with_target_bps(mut self, target_bps: u32) -> TestHarness<T> {
+        self.target_bps = target_bps;
+        self
+    }
+
+    /// Set the framing `run_all` tests. The default is a single length
+    /// octet followed by the payload.
+    pub fn with_framer(mut self, framer: &'static dyn FramingStrategy) -> TestHarness<T> {
+        self.framer = framer;
+        self
+    }
+
+    /// Release the port.
+    pub fn into_inner(self) -> T {
+        self.port
+    }
+
+    /// Send an octet and check the same octet comes back.
+    fn round_trip(&mut self, octet: u8) -> bool {
+        if self.port.putc(octet).is_err() {
+            return false;
+        }
+        match self.port.getc() {
+            Ok(echo) => echo == octet,
+            Err(_) => false,
+        }
+    }
+
+    /// Check that the test pattern is echoed back unchanged.
+    pub fn run_echo_test(&mut self) -> bool {
+        TEST_PATTERN.iter().all(|octet| self.round_trip(*octet))
+    }
+
+    /// Check that octets can be looped back at least as fast as
+    /// `target_bps` bits of payload per second.
+    pub fn run_throughput_test(&mut self, target_bps: u32) -> bool {
+        let start = (self.micros)();
+        for idx in 0..THROUGHPUT_OCTETS {
+            if !self.round_trip(idx as u8) {
+                return false;
+            }
+        }
+        let elapsed = u64::from((self.micros)().wrapping_sub(start));
+        let bits = u64::from(THROUGHPUT_OCTETS * 8);
+        elapsed == 0 || bits * 1_000_000 / elapsed >= u64::from(target_bps)
+    }
+
+    /// Check that the test pattern survives being framed with `framer`,
+    /// looped back, and de-framed.
+    pub fn run_framing_test(&mut self, framer: &dyn FramingStrategy) -> bool {
+        let mut frame = [0u8; MAX_FRAME];
+        let frame_len = match framer.encode(TEST_PATTERN, &mut frame) {
+            Some(len) if len <= frame.len() => len,
+            _ => return false,
+        };
+        for octet in &frame[..frame_len] {
+            if !self.round_trip(*octet) {
+                return false;
+            }
+        }
+        let mut payload = [0u8; MAX_FRAME];
+        match framer.decode(&frame[..frame_len], &mut payload) {
+            Some(len) => payload.get(..len) == Some(TEST_PATTERN),
+            None => false,
+        }
+    }
+
+    /// Run the echo test, the throughput test (against the target set with
+    /// `with_target_bps`) and the framing test (with the framing set with
+    /// `with_framer`) and collect the results.
+    pub fn run_all(&mut self) -> TestReport {
+        let mut report = TestReport::new();
+        let passed = self.run_echo_test();
+        report.record("echo", passed);
+        let target_bps = self.target_bps;
+        let passed = self.run_throughput_test(target_bps);
+        report.record("throughput", passed);
+        let framer = self.framer;
+        let passed = self.run_framing_test(framer);
+        report.record("framing", passed);
+        report
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
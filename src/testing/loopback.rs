@@ -0,0 +1,156 @@
+//! A software loopback port, for testing code which uses the serial traits
+//! without any hardware.
+
+use core::cell::Cell;
+
+use crate::{ImmutNonBlockingRx, ImmutNonBlockingTx, MutBlockingRx, MutBlockingTx};
+
+/// What a `Loopback` does with an octet sent while its buffer is full.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Refuse the octet - the blocking traits return
+    /// `LoopbackError::Overflow` and the non-blocking traits return
+    /// `Ok(None)`.
+    Error,
+    /// Silently drop the octet, reporting it as sent.
+    Drop,
+}
+
+/// The ways in which a `Loopback` can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LoopbackError {
+    /// An octet was sent while the buffer was full.
+    Overflow,
+    /// A blocking read was attempted while the buffer was empty. A real port
+    /// would block for ever, so this is reported instead.
+    Empty,
+}
+
+/// A port whose transmitter is wired straight to its receiver, through a
+/// ring buffer of `N` octets.
+///
+/// Octets sent with `putc`/`puts` (or `putc_try`/`puts_try`) can be read
+/// back straight away with `getc`/`gets` (or `getc_try`/`gets_try`).
+///
+/// ```
+/// use embedded_serial::MutBlockingTx;
+/// use embedded_serial::testing::Loopback;
+///
+/// let mut port = Loopback::<8>::new();
+/// port.puts(b"AT\r\n").unwrap();
+/// assert!(port.rx_buffer().eq(b"AT\r\n".iter().cloned()));
+/// ```
+pub struct Loopback<const N: usize> {
+    buffer: [Cell<u8>; N],
+    head: Cell<usize>,
+    len: Cell<usize>,
+    policy: OverflowPolicy,
+}
+
+impl<const N: usize> Loopback<N> {
+    /// Create an empty loopback which returns errors on overflow.
+    pub fn new() -> Loopback<N> {
+        Loopback::with_policy(OverflowPolicy::Error)
+    }
+
+    const CHECK: () = assert!(N >= 1, "a Loopback needs room for at least one octet");
+
+    /// Create an empty loopback with the given overflow policy.
+    pub fn with_policy(policy: OverflowPolicy) -> Loopback<N> {
+        let () = Self::CHECK;
+        Loopback {
+            buffer: core::array::from_fn(|_| Cell::new(0)),
+            head: Cell::new(0),
+            len: Cell::new(0),
+            policy,
+        }
+    }
+
+    /// The octets waiting to be received, oldest first.
+    pub fn rx_buffer(&self) -> impl Iterator<Item = u8> + '_ {
+        let head = self.head.get();
+        (0..self.len.get()).map(move |idx| self.buffer[(head + idx) % N].get())
+    }
+
+    /// Returns true if an octet was stored, false if the buffer was full.
+    fn push(&self, ch: u8) -> bool {
+        let len = self.len.get();
+        if len == N {
+            return false;
+        }
+        self.buffer[(self.head.get() + len) % N].set(ch);
+        self.len.set(len + 1);
+        true
+    }
+
+    fn pop(&self) -> Option<u8> {
+        let len = self.len.get();
+        if len == 0 {
+            return None;
+        }
+        let head = self.head.get();
+        self.head.set((head + 1) % N);
+        self.len.set(len - 1);
+        Some(self.buffer[head].get())
+    }
+}
+
+impl<const N: usize> Default for Loopback<N> {
+    fn default() -> Loopback<N> {
+        Loopback::new()
+    }
+}
+
+impl<const N: usize> MutBlockingTx for Loopback<N> {
+    type Error = LoopbackError;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        if self.push(ch) || self.policy == OverflowPolicy::Drop {
+            Ok(())
+        } else {
+            Err(LoopbackError::Overflow)
+        }
+    }
+}
+
+impl<const N: usize> MutBlockingRx for Loopback<N> {
+    type Error = LoopbackError;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        self.pop().ok_or(LoopbackError::Empty)
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        Ok(self.len.get())
+    }
+}
+
+impl<const N: usize> ImmutNonBlockingTx for Loopback<N> {
+    type Error = LoopbackError;
+
+    fn putc_try(&self, ch: u8) -> Result<Option<u8>, Self::Error> {
+        if self.push(ch) || self.policy == OverflowPolicy::Drop {
+            Ok(Some(ch))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<const N: usize> ImmutNonBlockingRx for Loopback<N> {
+    type Error = LoopbackError;
+
+    fn getc_try(&self) -> Result<Option<u8>, Self::Error> {
+        Ok(self.pop())
+    }
+
+    fn bytes_available(&self) -> Result<usize, Self::Error> {
+        Ok(self.len.get())
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,156 @@
+//! A scripted port, for testing protocol drivers.
+
+use crate::error::NoError;
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// One step in a `MockSerial` script.
+#[derive(Debug, Copy, Clone)]
+enum Step<'a> {
+    Write(&'a [u8]),
+    Respond(&'a [u8]),
+}
+
+/// A port which follows a script of expected writes and canned responses,
+/// and panics as soon as the code under test strays from it.
+///
+/// The script can hold up to `N` steps.
+///
+/// ```
+/// use embedded_serial::{MutBlockingRx, MutBlockingTx};
+/// use embedded_serial::testing::MockSerial;
+///
+/// let mut port: MockSerial = MockSerial::new()
+///     .expect_write(b"AT\r\n")
+///     .respond_with(b"OK\r\n");
+/// port.puts(b"AT\r\n").unwrap();
+/// let mut response = [0u8; 4];
+/// port.gets(&mut response).unwrap();
+/// assert_eq!(&response, b"OK\r\n");
+/// port.assert_complete();
+/// ```
+pub struct MockSerial<'a, const N: usize = 16> {
+    steps: [Option<Step<'a>>; N],
+    len: usize,
+    step: usize,
+    offset: usize,
+}
+
+impl<'a, const N: usize> MockSerial<'a, N> {
+    /// Create a port with an empty script.
+    pub fn new() -> MockSerial<'a, N> {
+        MockSerial {
+            steps: [None; N],
+            len: 0,
+            step: 0,
+            offset: 0,
+        }
+    }
+
+    /// Add a step where the code under test must write exactly `data`.
+    ///
+    /// Panics if the script is full.
+    pub fn expect_write(self, data: &'a [u8]) -> MockSerial<'a, N> {
+        self.push(Step::Write(data))
+    }
+
+    /// Add a step where the code under test reads `data`.
+    ///
+    /// Panics if the script is full.
+    pub fn respond_with(self, data: &'a [u8]) -> MockSerial<'a, N> {
+        self.push(Step::Respond(data))
+    }
+
+    /// Panics unless every step of the script has been completed.
+    pub fn assert_complete(&self) {
+        assert!(self.current().is_none(),
+                "MockSerial: script not complete, stopped in step {} of {}",
+                self.step + 1,
+                self.len);
+    }
+
+    fn push(mut self, step: Step<'a>) -> MockSerial<'a, N> {
+        assert!(self.len < N, "MockSerial: script is full ({} steps)", N);
+        // Zero length steps would never be completed, so skip them.
+        let data = match step {
+            Step::Write(data) | Step::Respond(data) => data,
+        };
+        if !data.is_empty() {
+            self.steps[self.len] = Some(step);
+            self.len += 1;
+        }
+        self
+    }
+
+    fn current(&self) -> Option<Step<'a>> {
+        self.steps.get(self.step).cloned().flatten()
+    }
+
+    /// Move on one octet through the current step.
+    fn advance(&mut self, step_len: usize) {
+        self.offset += 1;
+        if self.offset == step_len {
+            self.step += 1;
+            self.offset = 0;
+        }
+    }
+}
+
+impl<'a, const N: usize> Default for MockSerial<'a, N> {
+    fn default() -> MockSerial<'a, N> {
+        MockSerial::new()
+    }
+}
+
+impl<'a, const N: usize> MutBlockingTx for MockSerial<'a, N> {
+    type Error = NoError;
+
+    /// Panics unless `ch` is the next octet the script expects to be
+    /// written.
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        match self.current() {
+            Some(Step::Write(expected)) => {
+                assert!(expected[self.offset] == ch,
+                        "MockSerial: step {}: expected 0x{:02x} at offset {}, got 0x{:02x}",
+                        self.step + 1,
+                        expected[self.offset],
+                        self.offset,
+                        ch);
+                self.advance(expected.len());
+                Ok(())
+            }
+            Some(Step::Respond(_)) => {
+                panic!("MockSerial: step {}: unexpected write of 0x{:02x}, expected a read",
+                       self.step + 1,
+                       ch)
+            }
+            None => panic!("MockSerial: unexpected write of 0x{:02x} after end of script", ch),
+        }
+    }
+}
+
+impl<'a, const N: usize> MutBlockingRx for MockSerial<'a, N> {
+    type Error = NoError;
+
+    /// Returns the next octet of the current response. Panics if the script
+    /// is expecting a write instead.
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        match self.current() {
+            Some(Step::Respond(data)) => {
+                let ch = data[self.offset];
+                self.advance(data.len());
+                Ok(ch)
+            }
+            Some(Step::Write(_)) => {
+                panic!("MockSerial: step {}: unexpected read, expected a write",
+                       self.step + 1)
+            }
+            None => panic!("MockSerial: unexpected read after end of script"),
+        }
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
@@ -0,0 +1,21 @@
+//! # Testing
+//!
+//! Tools for testing serial ports, and the code which uses them.
+
+mod buffers;
+mod harness;
+mod loopback;
+mod mock;
+mod pipe;
+
+pub use self::buffers::{SliceRx, SliceRxError, VecTx, VecTxError};
+pub use self::harness::{FramingStrategy, TestHarness, TestReport};
+pub use self::loopback::{Loopback, LoopbackError, OverflowPolicy};
+pub use self::mock::MockSerial;
+pub use self::pipe::{PipePeer, PipeSerial};
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
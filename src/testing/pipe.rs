@@ -0,0 +1,106 @@
+//! A pair of connected software ports, for testing both ends of a protocol
+//! without any hardware.
+
+use crate::testing::{Loopback, LoopbackError};
+use crate::{MutBlockingRx, MutBlockingTx};
+
+/// One end of a software serial link, backed by two ring buffers of `N`
+/// octets - one for each direction. The other end is reached with `peer`.
+///
+/// Octets sent on one end are received on the other. As with `Loopback`, a
+/// send to a full buffer returns `LoopbackError::Overflow` and a read from
+/// an empty one returns `LoopbackError::Empty`, rather than blocking.
+///
+/// ```
+/// use embedded_serial::{MutBlockingRx, MutBlockingTx};
+/// use embedded_serial::testing::PipeSerial;
+///
+/// let mut host = PipeSerial::<16>::new();
+/// host.puts(b"AT\r\n").unwrap();
+/// {
+///     let mut device = host.peer();
+///     let mut request = [0u8; 4];
+///     device.gets(&mut request).unwrap();
+///     assert_eq!(&request, b"AT\r\n");
+///     device.puts(b"OK\r\n").unwrap();
+/// }
+/// assert_eq!(host.getc(), Ok(b'O'));
+/// ```
+pub struct PipeSerial<const N: usize> {
+    to_peer: Loopback<N>,
+    from_peer: Loopback<N>,
+}
+
+/// The other end of a `PipeSerial`, returned by `PipeSerial::peer`.
+pub struct PipePeer<'a, const N: usize> {
+    pipe: &'a mut PipeSerial<N>,
+}
+
+impl<const N: usize> PipeSerial<N> {
+    /// Create a link with both directions empty.
+    pub fn new() -> PipeSerial<N> {
+        PipeSerial {
+            to_peer: Loopback::new(),
+            from_peer: Loopback::new(),
+        }
+    }
+
+    /// Get the other end of the link. It borrows this end, so the two ends
+    /// are used in turn - e.g. send a request, drive the peer to answer it,
+    /// then check the answer.
+    pub fn peer(&mut self) -> PipePeer<'_, N> {
+        PipePeer { pipe: self }
+    }
+}
+
+impl<const N: usize> Default for PipeSerial<N> {
+    fn default() -> PipeSerial<N> {
+        PipeSerial::new()
+    }
+}
+
+impl<const N: usize> MutBlockingTx for PipeSerial<N> {
+    type Error = LoopbackError;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.to_peer.putc(ch)
+    }
+}
+
+impl<const N: usize> MutBlockingRx for PipeSerial<N> {
+    type Error = LoopbackError;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        self.from_peer.getc()
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        MutBlockingRx::bytes_available(&mut self.from_peer)
+    }
+}
+
+impl<'a, const N: usize> MutBlockingTx for PipePeer<'a, N> {
+    type Error = LoopbackError;
+
+    fn putc(&mut self, ch: u8) -> Result<(), Self::Error> {
+        self.pipe.from_peer.putc(ch)
+    }
+}
+
+impl<'a, const N: usize> MutBlockingRx for PipePeer<'a, N> {
+    type Error = LoopbackError;
+
+    fn getc(&mut self) -> Result<u8, Self::Error> {
+        self.pipe.to_peer.getc()
+    }
+
+    fn bytes_available(&mut self) -> Result<usize, Self::Error> {
+        MutBlockingRx::bytes_available(&mut self.pipe.to_peer)
+    }
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************
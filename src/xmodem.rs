@@ -0,0 +1,241 @@
+//! # XMODEM-CRC
+//!
+//! XMODEM transfers data in 128 octet blocks, each acknowledged by the
+//! receiver before the next is sent. This is the CRC variant: the receiver
+//! starts the transfer by sending `C`, and every block is protected by a
+//! CRC-16/XMODEM rather than an arithmetic checksum.
+//!
+//! Both ends use the same port for transmit and receive, so the port's
+//! transmit and receive errors must be the same type.
+
+use crate::crc::crc16_ccitt;
+use crate::{MutBlockingRxWithTimeout, MutBlockingTx};
+
+/// The number of data octets in each block.
+pub const BLOCK_SIZE: usize = 128;
+
+/// How many times a block (or the start of the transfer) is retried before
+/// giving up.
+pub const MAX_RETRIES: u8 = 10;
+
+const SOH: u8 = 0x01;
+const EOT: u8 = 0x04;
+const ACK: u8 = 0x06;
+const NAK: u8 = 0x15;
+const CAN: u8 = 0x18;
+const CRC_MODE: u8 = b'C';
+// Pads the final block out to BLOCK_SIZE.
+const SUB: u8 = 0x1A;
+
+/// The ways in which an XMODEM transfer can fail.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum XmodemError<E> {
+    /// A block was rejected (or arrived corrupted) `MAX_RETRIES` times in a
+    /// row.
+    Nak,
+    /// The other end cancelled the transfer.
+    Cancelled,
+    /// Nothing was heard from the other end, `MAX_RETRIES` times in a row.
+    Timeout,
+    /// The sender skipped a block, so the transfer was cancelled.
+    OutOfSequence,
+    /// The port returned an error.
+    Inner(E),
+}
+
+/// Send `data` to an XMODEM-CRC receiver.
+///
+/// The final block is padded with SUB (0x1A) octets. `timeout` is used each
+/// time a response is awaited from the receiver.
+pub fn xmodem_send<T>(port: &mut T,
+                      data: &[u8],
+                      timeout: &T::Timeout)
+                      -> Result<(), XmodemError<<T as MutBlockingTx>::Error>>
+    where T: MutBlockingTx + MutBlockingRxWithTimeout<Error = <T as MutBlockingTx>::Error>
+{
+    wait_for_start(port, timeout)?;
+    for (index, chunk) in data.chunks(BLOCK_SIZE).enumerate() {
+        let block_num = (index + 1) as u8;
+        send_with_retry(port, timeout, |port| send_block(port, block_num, chunk))?;
+    }
+    send_with_retry(port, timeout, |port| port.putc(EOT))
+}
+
+/// Receive data from an XMODEM-CRC sender, passing each block to
+/// `on_block` as it arrives.
+///
+/// Returns the number of octets received, which is always a multiple of
+/// `BLOCK_SIZE` as it includes the sender's padding. `timeout` is used for
+/// every octet received.
+pub fn xmodem_receive<T, F>(port: &mut T,
+                            timeout: &T::Timeout,
+                            mut on_block: F)
+                            -> Result<usize, XmodemError<<T as MutBlockingTx>::Error>>
+    where T: MutBlockingTx + MutBlockingRxWithTimeout<Error = <T as MutBlockingTx>::Error>,
+          F: FnMut(&[u8; BLOCK_SIZE])
+{
+    let mut expected: u8 = 1;
+    let mut received: usize = 0;
+    let mut retries: u8 = 0;
+    // Until the first block arrives, we keep asking for CRC mode.
+    let mut response = CRC_MODE;
+    let mut block = [0u8; BLOCK_SIZE];
+    loop {
+        port.putc(response).map_err(XmodemError::Inner)?;
+        response = match port.getc_wait(timeout).map_err(XmodemError::Inner)? {
+            Some(SOH) => {
+                match receive_block(port, timeout, &mut block)? {
+                    Some(num) if num == expected => {
+                        on_block(&block);
+                        received += BLOCK_SIZE;
+                        expected = expected.wrapping_add(1);
+                        retries = 0;
+                        ACK
+                    }
+                    // Our ACK for the previous block was lost, so it was sent
+                    // again. Before the first block there is no previous
+                    // block, so a block 0 is out of sequence.
+                    Some(num) if received > 0 && num == expected.wrapping_sub(1) => {
+                        retries = 0;
+                        ACK
+                    }
+                    Some(_) => {
+                        port.putc(CAN).map_err(XmodemError::Inner)?;
+                        port.putc(CAN).map_err(XmodemError::Inner)?;
+                        return Err(XmodemError::OutOfSequence);
+                    }
+                    None => {
+                        retries += 1;
+                        if retries == MAX_RETRIES {
+                            return Err(XmodemError::Nak);
+                        }
+                        NAK
+                    }
+                }
+            }
+            Some(EOT) => {
+                port.putc(ACK).map_err(XmodemError::Inner)?;
+                return Ok(received);
+            }
+            Some(CAN) => return Err(XmodemError::Cancelled),
+            other => {
+                retries += 1;
+                if retries == MAX_RETRIES {
+                    return Err(if other.is_none() {
+                        XmodemError::Timeout
+                    } else {
+                        XmodemError::Nak
+                    });
+                }
+                if expected == 1 && received == 0 {
+                    CRC_MODE
+                } else {
+                    NAK
+                }
+            }
+        };
+    }
+}
+
+/// Wait for the receiver to ask for a CRC mode transfer.
+fn wait_for_start<T>(port: &mut T,
+                     timeout: &T::Timeout)
+                     -> Result<(), XmodemError<<T as MutBlockingTx>::Error>>
+    where T: MutBlockingTx + MutBlockingRxWithTimeout<Error = <T as MutBlockingTx>::Error>
+{
+    for _ in 0..MAX_RETRIES {
+        match port.getc_wait(timeout).map_err(XmodemError::Inner)? {
+            Some(CRC_MODE) => return Ok(()),
+            Some(CAN) => return Err(XmodemError::Cancelled),
+            _ => {}
+        }
+    }
+    Err(XmodemError::Timeout)
+}
+
+/// Call `send` until the receiver ACKs what it sent.
+fn send_with_retry<T, F>(port: &mut T,
+                         timeout: &T::Timeout,
+                         mut send: F)
+                         -> Result<(), XmodemError<<T as MutBlockingTx>::Error>>
+    where T: MutBlockingTx + MutBlockingRxWithTimeout<Error = <T as MutBlockingTx>::Error>,
+          F: FnMut(&mut T) -> Result<(), <T as MutBlockingTx>::Error>
+{
+    let mut last_error = XmodemError::Timeout;
+    for _ in 0..MAX_RETRIES {
+        send(port).map_err(XmodemError::Inner)?;
+        loop {
+            match port.getc_wait(timeout).map_err(XmodemError::Inner)? {
+                Some(ACK) => return Ok(()),
+                Some(NAK) => {
+                    last_error = XmodemError::Nak;
+                    break;
+                }
+                Some(CAN) => return Err(XmodemError::Cancelled),
+                // Line noise - keep waiting for a proper response.
+                Some(_) => {}
+                None => {
+                    last_error = XmodemError::Timeout;
+                    break;
+                }
+            }
+        }
+    }
+    Err(last_error)
+}
+
+/// Send one block, padding `chunk` out to `BLOCK_SIZE`.
+fn send_block<T>(port: &mut T, block_num: u8, chunk: &[u8]) -> Result<(), T::Error>
+    where T: MutBlockingTx
+{
+    port.putc(SOH)?;
+    port.putc(block_num)?;
+    port.putc(!block_num)?;
+    let mut crc: u16 = 0;
+    for idx in 0..BLOCK_SIZE {
+        let octet = if idx < chunk.len() { chunk[idx] } else { SUB };
+        port.putc(octet)?;
+        crc = crc16_ccitt(crc, octet);
+    }
+    port.putc((crc >> 8) as u8)?;
+    port.putc(crc as u8)
+}
+
+/// Receive the rest of a block, after its SOH. Returns the block number if
+/// the block is intact, or `None` if it was corrupted or cut short.
+fn receive_block<T>(port: &mut T,
+                    timeout: &T::Timeout,
+                    block: &mut [u8; BLOCK_SIZE])
+                    -> Result<Option<u8>, XmodemError<<T as MutBlockingTx>::Error>>
+    where T: MutBlockingTx + MutBlockingRxWithTimeout<Error = <T as MutBlockingTx>::Error>
+{
+    let mut header = [0u8; 2];
+    let mut trailer = [0u8; 2];
+    let complete = fill(port, timeout, &mut header)? && fill(port, timeout, block)? &&
+                   fill(port, timeout, &mut trailer)?;
+    if !complete || header[0] != !header[1] {
+        return Ok(None);
+    }
+    let crc = block.iter().fold(0, |crc, octet| crc16_ccitt(crc, *octet));
+    if crc != (u16::from(trailer[0]) << 8 | u16::from(trailer[1])) {
+        return Ok(None);
+    }
+    Ok(Some(header[0]))
+}
+
+/// Fill `buffer`, returning false on a timeout.
+fn fill<T>(port: &mut T,
+           timeout: &T::Timeout,
+           buffer: &mut [u8])
+           -> Result<bool, XmodemError<<T as MutBlockingTx>::Error>>
+    where T: MutBlockingTx + MutBlockingRxWithTimeout<Error = <T as MutBlockingTx>::Error>
+{
+    let count = port.gets_wait(buffer, timeout).map_err(|(_, e)| XmodemError::Inner(e))?;
+    Ok(count == buffer.len())
+}
+
+// ****************************************************************************
+//
+// End Of File
+//
+// ****************************************************************************